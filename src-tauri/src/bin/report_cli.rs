@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -5,8 +6,14 @@ use std::sync::Arc;
 use anyhow::{anyhow, bail, Context, Result};
 use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use clap::{Parser, ValueEnum};
-use gitlog_ai_reporter_lib::models::{AppConfig, RepoGroup, ReportType};
-use gitlog_ai_reporter_lib::services::{GitService, LLMService, ReportService};
+use gitlog_ai_reporter_lib::models::{
+    AppConfig, AuthorMatchMode, BranchSelection, CommitFilter, MergePolicy, OwnershipOptions,
+    RepoError, RepoGroup, RepoWarning, ReportType, ScanOptions, TimeKey, WorkEstimate,
+};
+use gitlog_ai_reporter_lib::services::collect_service::DEFAULT_CONCURRENCY;
+use gitlog_ai_reporter_lib::services::session_estimator::SessionEstimator;
+use gitlog_ai_reporter_lib::services::time_zone::TimeZone;
+use gitlog_ai_reporter_lib::services::{CollectService, GitService, LLMService, ReportService};
 
 #[derive(Parser, Debug)]
 #[command(
@@ -15,9 +22,21 @@ use gitlog_ai_reporter_lib::services::{GitService, LLMService, ReportService};
 )]
 struct CliArgs {
     /// Git 仓库路径，可多次传入 --repo
-    #[arg(long = "repo", required = true)]
+    #[arg(long = "repo", required_unless_present = "scan_roots")]
     repo_paths: Vec<PathBuf>,
 
+    /// 递归扫描目录下的仓库，可多次传入 --scan；窗口内无提交的仓库会被跳过
+    #[arg(long = "scan", value_name = "DIR")]
+    scan_roots: Vec<PathBuf>,
+
+    /// 扫描的最大目录深度
+    #[arg(long = "scan-depth", value_name = "N")]
+    scan_depth: Option<usize>,
+
+    /// 扫描时额外忽略的目录（目录名或相对路径通配），可多次传入
+    #[arg(long = "scan-ignore", value_name = "GLOB")]
+    scan_ignore: Vec<String>,
+
     /// 报告起始日期 (YYYY-MM-DD)
     #[arg(long = "from", value_name = "DATE", required = true)]
     range_from: String,
@@ -41,6 +60,124 @@ struct CliArgs {
     /// 模板 ID（CLI 模式暂不支持，预留参数）
     #[arg(long = "template-id")]
     template_id: Option<String>,
+
+    /// 只统计指定作者（姓名或邮箱），可多次传入 --author
+    #[arg(long = "author", value_name = "NAME|EMAIL")]
+    authors: Vec<String>,
+
+    /// 作者匹配方式
+    #[arg(long = "author-match", value_enum, default_value = "exact")]
+    author_match: AuthorMatchArg,
+
+    /// 只遍历指定分支，可多次传入 --branch（默认仅 HEAD）
+    #[arg(
+        long = "branch",
+        value_name = "BRANCH",
+        conflicts_with_all = ["all_branches", "branch_glob"]
+    )]
+    branches: Vec<String>,
+
+    /// 遍历全部本地分支
+    #[arg(long = "all-branches", conflicts_with = "branch_glob")]
+    all_branches: bool,
+
+    /// 遍历匹配通配符的引用（如 feature/* 或 refs/remotes/origin/*）
+    #[arg(long = "branch-glob", value_name = "PATTERN")]
+    branch_glob: Option<String>,
+
+    /// 时间窗口依据：committer（合入时间）或 author（编写时间）
+    #[arg(long = "time-key", value_enum, default_value = "committer")]
+    time_key: TimeKeyArg,
+
+    /// 合并提交处理策略
+    #[arg(long = "merge-policy", value_enum, default_value = "all")]
+    merge_policy: MergePolicyArg,
+
+    /// 只统计触及匹配路径的提交，可多次传入 --path（如 services/billing/**）
+    #[arg(long = "path", value_name = "GLOB")]
+    paths: Vec<String>,
+
+    /// 排除匹配路径的文件，可多次传入 --exclude-path（如 **/*.lock）
+    #[arg(long = "exclude-path", value_name = "GLOB")]
+    exclude_paths: Vec<String>,
+
+    /// 进入已初始化的子模块，采集其提交并关联父仓库中的指针更新
+    #[arg(long = "submodules")]
+    submodules: bool,
+
+    /// 数据不完整（浅克隆历史缺失或仓库采集失败）时直接失败，而不是带告警生成报告
+    #[arg(long = "fail-on-incomplete")]
+    fail_on_incomplete: bool,
+
+    /// 在提示词中附带提交 diff（按字符预算分配，锁文件等路径除外）
+    #[arg(long = "include-diffs")]
+    include_diffs: bool,
+
+    /// diff 的字符总预算，默认取配置文件中的设置
+    #[arg(long = "diff-budget", value_name = "CHARS")]
+    diff_budget: Option<usize>,
+
+    /// 改动热点按目录汇总的层级，默认取配置文件中的设置
+    #[arg(long = "hotspot-depth", value_name = "N")]
+    hotspot_depth: Option<usize>,
+
+    /// 工时估算中同一工作时段内提交的最大间隔（分钟），默认取配置文件中的设置
+    #[arg(long = "session-gap", value_name = "MINUTES")]
+    session_gap: Option<u32>,
+
+    /// 工时估算中每个工作时段首个提交前计入的时间（分钟），默认取配置文件中的设置
+    #[arg(long = "session-lead", value_name = "MINUTES")]
+    session_lead: Option<u32>,
+
+    /// 在报告中附带基于 blame 的代码归属（配置文件中已开启时无需指定）
+    #[arg(long = "ownership")]
+    ownership: bool,
+
+    /// 同时采集的仓库数
+    #[arg(long = "jobs", value_name = "N", default_value_t = DEFAULT_CONCURRENCY)]
+    jobs: usize,
+}
+
+impl CliArgs {
+    /// 根据命令行参数确定分支选择方式
+    fn branch_selection(&self) -> BranchSelection {
+        if self.all_branches {
+            BranchSelection::AllLocal
+        } else if let Some(pattern) = &self.branch_glob {
+            BranchSelection::Glob {
+                pattern: pattern.clone(),
+            }
+        } else if !self.branches.is_empty() {
+            BranchSelection::Named {
+                branches: self.branches.clone(),
+            }
+        } else {
+            BranchSelection::Head
+        }
+    }
+
+    /// 扫描选项：在默认忽略规则基础上追加命令行传入的规则，分支与时间依据与采集一致
+    fn scan_options(&self) -> ScanOptions {
+        let mut options = ScanOptions {
+            branches: self.branch_selection(),
+            time_key: self.time_key.into(),
+            ..Default::default()
+        };
+        if let Some(depth) = self.scan_depth {
+            options.max_depth = depth;
+        }
+        options.ignore.extend(self.scan_ignore.iter().cloned());
+        options
+    }
+
+    /// 合并包含与排除路径（排除规则以 `!` 前缀表示）
+    fn path_patterns(&self) -> Vec<String> {
+        self.paths
+            .iter()
+            .cloned()
+            .chain(self.exclude_paths.iter().map(|p| format!("!{}", p)))
+            .collect()
+    }
 }
 
 #[derive(Copy, Clone, Debug, ValueEnum)]
@@ -49,6 +186,57 @@ enum ReportTypeArg {
     Monthly,
 }
 
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum AuthorMatchArg {
+    Exact,
+    IgnoreCase,
+    Glob,
+}
+
+impl From<AuthorMatchArg> for AuthorMatchMode {
+    fn from(value: AuthorMatchArg) -> Self {
+        match value {
+            AuthorMatchArg::Exact => AuthorMatchMode::Exact,
+            AuthorMatchArg::IgnoreCase => AuthorMatchMode::IgnoreCase,
+            AuthorMatchArg::Glob => AuthorMatchMode::Glob,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum TimeKeyArg {
+    Committer,
+    Author,
+}
+
+impl From<TimeKeyArg> for TimeKey {
+    fn from(value: TimeKeyArg) -> Self {
+        match value {
+            TimeKeyArg::Committer => TimeKey::Committer,
+            TimeKeyArg::Author => TimeKey::Author,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum MergePolicyArg {
+    All,
+    ExcludeMerges,
+    FirstParent,
+    MergesOnly,
+}
+
+impl From<MergePolicyArg> for MergePolicy {
+    fn from(value: MergePolicyArg) -> Self {
+        match value {
+            MergePolicyArg::All => MergePolicy::All,
+            MergePolicyArg::ExcludeMerges => MergePolicy::ExcludeMerges,
+            MergePolicyArg::FirstParent => MergePolicy::FirstParent,
+            MergePolicyArg::MergesOnly => MergePolicy::MergesOnly,
+        }
+    }
+}
+
 impl From<ReportTypeArg> for ReportType {
     fn from(value: ReportTypeArg) -> Self {
         match value {
@@ -77,21 +265,107 @@ async fn main() -> Result<()> {
 
     let app_config = load_config(&args.config)?;
     let llm_service = Arc::new(LLMService::new(
-        app_config.llm_provider,
-        Some(app_config.proxy_config),
+        app_config.llm_provider.clone(),
+        Some(app_config.proxy_config.clone()),
     ));
-    let report_service = ReportService::new(llm_service);
+    let mut diff_inclusion = app_config.diff_inclusion.clone();
+    diff_inclusion.enabled |= args.include_diffs;
+    if let Some(budget) = args.diff_budget {
+        diff_inclusion.max_chars = budget;
+    }
+    let mut hotspots = app_config.hotspots.clone();
+    if let Some(depth) = args.hotspot_depth {
+        hotspots.depth = depth;
+    }
+    let mut sessions = app_config.sessions.clone();
+    if let Some(gap) = args.session_gap {
+        sessions.max_gap_minutes = gap;
+    }
+    if let Some(lead) = args.session_lead {
+        sessions.first_commit_minutes = lead;
+    }
+    let ownership = match &app_config.ownership {
+        Some(ownership) => Some(ownership.clone()),
+        None if args.ownership => Some(OwnershipOptions::default()),
+        None => None,
+    };
+    // 无法识别的时区按 UTC 统计，采集告警中会逐仓库提示
+    let timezone = TimeZone::parse_or_utc(&app_config.timezone).0;
+    let report_service = ReportService::new(llm_service)
+        .with_diff_inclusion(diff_inclusion)
+        .with_hotspot_options(hotspots)
+        .with_session_options(sessions.clone())
+        .with_ownership(ownership)
+        .with_author_aliases(app_config.author_aliases.clone())
+        .with_timezone(&app_config.timezone)
+        .with_language_mappings(&app_config.language_mappings)
+        .map_err(anyhow::Error::msg)?;
 
-    let mut repo_groups = Vec::new();
-    for repo_path in &args.repo_paths {
-        let group = collect_repo_group(repo_path, from_ts, to_ts)?;
-        repo_groups.push(group);
+    let filter = CommitFilter {
+        authors: args.authors.clone(),
+        author_match: args.author_match.into(),
+        branches: args.branch_selection(),
+        time_key: args.time_key.into(),
+        merge_policy: args.merge_policy.into(),
+        paths: args.path_patterns(),
+        include_submodules: args.submodules,
+    };
+
+    let repo_paths = resolve_repo_paths(&args, from_ts, to_ts)?
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+
+    let collected = CollectService::collect_repositories(
+        repo_paths,
+        from_ts,
+        to_ts,
+        filter,
+        app_config.clone(),
+        args.jobs,
+        |progress| eprintln!("{}", progress.describe()),
+    )
+    .await;
+
+    // 单个仓库失败只记为告警，保留空分组继续生成报告
+    let mut repo_groups = collected.repo_groups;
+    repo_groups.extend(collected.errors.iter().map(failed_repo_group));
+
+    for group in &repo_groups {
+        for sub_group in group.flatten() {
+            for warning in &sub_group.warnings {
+                eprintln!("⚠️  {}：{}", sub_group.repo_name, warning.describe());
+            }
+        }
     }
 
     if repo_groups.is_empty() {
         bail!("选定的仓库在指定时间范围内没有提交");
     }
 
+    if args.fail_on_incomplete {
+        let incomplete: Vec<String> = repo_groups
+            .iter()
+            .flat_map(RepoGroup::flatten)
+            .flat_map(|group| {
+                group
+                    .warnings
+                    .iter()
+                    .filter(|w| w.is_incomplete())
+                    .map(move |w| format!("{}：{}", group.repo_name, w.describe()))
+            })
+            .collect();
+
+        if !incomplete.is_empty() {
+            bail!(
+                "数据不完整，已按 --fail-on-incomplete 终止：\n{}",
+                incomplete.join("\n")
+            );
+        }
+    }
+
+    let work_estimate = SessionEstimator::new(sessions, timezone).estimate(&repo_groups);
+
     let report = match args.report_type {
         ReportTypeArg::Weekly => report_service
             .generate_weekly(repo_groups, None, None)
@@ -118,10 +392,28 @@ async fn main() -> Result<()> {
         args.output.display(),
         report.commits.len()
     );
+    print_work_estimate(&work_estimate);
 
     Ok(())
 }
 
+/// 输出按提交时间估算的工时
+fn print_work_estimate(estimate: &WorkEstimate) {
+    println!(
+        "⏱️  预估工时：约 {} 小时（{} 个工作时段，间隔上限 {} 分钟）",
+        estimate.total_hours, estimate.sessions, estimate.max_gap_minutes
+    );
+    for author in &estimate.by_author {
+        println!("   {}：约 {} 小时", author.name, author.hours);
+    }
+    for repo in &estimate.by_repo {
+        println!("   [{}] 约 {} 小时", repo.name, repo.hours);
+    }
+    for day in &estimate.by_day {
+        println!("   {}：约 {} 小时", day.name, day.hours);
+    }
+}
+
 fn parse_date_start(value: &str) -> Result<i64> {
     let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")?;
     let datetime = NaiveDateTime::new(date, NaiveTime::from_hms_opt(0, 0, 0).unwrap());
@@ -142,24 +434,54 @@ fn load_config(path: &Path) -> Result<AppConfig> {
     Ok(config)
 }
 
-fn collect_repo_group(path: &Path, from_ts: i64, to_ts: i64) -> Result<RepoGroup> {
-    let repo_path_str = path
-        .to_str()
-        .ok_or_else(|| anyhow!("无法解析仓库路径"))?
-        .to_string();
+/// 合并 --repo 与 --scan 得到的仓库路径（去重，保持传入顺序）
+fn resolve_repo_paths(args: &CliArgs, from_ts: i64, to_ts: i64) -> Result<Vec<PathBuf>> {
+    let mut paths = args.repo_paths.clone();
+    let options = args.scan_options();
 
-    let git_service = GitService::open_repo(&repo_path_str).map_err(anyhow::Error::msg)?;
-    let repo_info = git_service
-        .get_repo_info()
-        .map_err(anyhow::Error::msg)?;
-    let commits = git_service
-        .get_commits(from_ts, to_ts)
-        .map_err(anyhow::Error::msg)?;
+    for root in &args.scan_roots {
+        let root_str = root
+            .to_str()
+            .ok_or_else(|| anyhow!("无法解析扫描目录: {}", root.display()))?;
+        let scanned = GitService::discover_repositories(root_str, from_ts, to_ts, &options)
+            .map_err(anyhow::Error::msg)?;
+        for error in &scanned.errors {
+            eprintln!(
+                "⚠️  跳过无法读取的仓库 {}：{}",
+                error.repo_path, error.message
+            );
+        }
+        paths.extend(
+            scanned
+                .repos
+                .into_iter()
+                .map(|info| PathBuf::from(info.path)),
+        );
+    }
 
-    Ok(RepoGroup {
-        repo_id: repo_info.path.clone(),
-        repo_name: repo_info.name,
-        repo_path: repo_info.path,
-        commits,
-    })
+    let mut seen = HashSet::new();
+    paths.retain(|p| seen.insert(p.clone()));
+
+    Ok(paths)
+}
+
+/// 采集失败的仓库：保留空分组并附带失败告警
+fn failed_repo_group(error: &RepoError) -> RepoGroup {
+    let repo_path = error.repo_path.clone();
+    let repo_name = Path::new(&repo_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| repo_path.clone());
+
+    RepoGroup {
+        repo_id: repo_path.clone(),
+        repo_name,
+        repo_path,
+        commits: Vec::new(),
+        releases: Vec::new(),
+        submodules: Vec::new(),
+        warnings: vec![RepoWarning::Failed {
+            message: error.message.clone(),
+        }],
+    }
 }
@@ -1,18 +1,45 @@
 // Git 相关 Tauri 命令
 
-use crate::models::{Commit, RepoInfo, RepoStats};
-use crate::services::{cache_service, GitService};
+use crate::models::{
+    BranchSelection, CollectResult, Commit, CommitFilter, DiffSummary, HotspotOptions, Hotspots,
+    OwnershipOptions, OwnershipSummary, Release, RepoGroup, RepoInfo, RepoStats, ScanOptions,
+    ScanResult,
+};
+use crate::services::collect_service::DEFAULT_CONCURRENCY;
+use crate::services::diff_summary;
+use crate::services::{cache_service, CollectService, GitService, StorageService};
+use tauri::{AppHandle, Emitter};
 
 #[tauri::command]
-pub async fn open_repository(path: String) -> Result<RepoInfo, String> {
+pub async fn open_repository(
+    path: String,
+    branches: Option<BranchSelection>,
+) -> Result<RepoInfo, String> {
     let git_service = GitService::open_repo(&path)?;
-    git_service.get_repo_info()
+    git_service.get_repo_info_with(&branches.unwrap_or_default())
 }
 
 #[tauri::command]
-pub async fn get_commits(path: String, from: i64, to: i64) -> Result<Vec<Commit>, String> {
-    let git_service = GitService::open_repo(&path)?;
-    git_service.get_commits(from, to)
+pub async fn scan_repositories(
+    root: String,
+    from: i64,
+    to: i64,
+    options: Option<ScanOptions>,
+) -> Result<ScanResult, String> {
+    GitService::discover_repositories(&root, from, to, &options.unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn get_commits(
+    path: String,
+    from: i64,
+    to: i64,
+    filter: Option<CommitFilter>,
+    app: AppHandle,
+) -> Result<Vec<Commit>, String> {
+    let config = StorageService::load_config(&app)?;
+    let git_service = GitService::open_repo_with_config(&path, &config)?;
+    git_service.get_commits_filtered(from, to, &filter.unwrap_or_default())
 }
 
 #[tauri::command]
@@ -21,11 +48,111 @@ pub async fn get_commit_diff(path: String, hash: String) -> Result<String, Strin
     git_service.get_commit_diff(&hash)
 }
 
+/// 提交的结构化 diff 摘要；threshold 为改用摘要文本的字节阈值
+#[tauri::command]
+pub async fn get_commit_diff_summary(
+    path: String,
+    hash: String,
+    threshold: Option<usize>,
+) -> Result<DiffSummary, String> {
+    let git_service = GitService::open_repo(&path)?;
+    git_service.summarize_commit_diff(
+        &hash,
+        &[],
+        threshold.unwrap_or(diff_summary::DEFAULT_THRESHOLD),
+    )
+}
+
+#[tauri::command]
+pub async fn get_repo_stats(
+    path: String,
+    from: i64,
+    to: i64,
+    filter: Option<CommitFilter>,
+    app: AppHandle,
+) -> Result<RepoStats, String> {
+    let config = StorageService::load_config(&app)?;
+    let git_service = GitService::open_repo_with_config(&path, &config)?;
+    let filter = filter.unwrap_or_default();
+    let commits = git_service.get_commits_filtered(from, to, &filter)?;
+    git_service.get_stats_filtered(&commits, &filter)
+}
+
+/// 统计时间窗口内改动最频繁的文件与目录
+#[tauri::command]
+pub async fn get_hotspots(
+    path: String,
+    from: i64,
+    to: i64,
+    filter: Option<CommitFilter>,
+    options: Option<HotspotOptions>,
+    app: AppHandle,
+) -> Result<Hotspots, String> {
+    let config = StorageService::load_config(&app)?;
+    let git_service = GitService::open_repo_with_config(&path, &config)?;
+    let options = options.unwrap_or(config.hotspots);
+    git_service.get_hotspots(from, to, &filter.unwrap_or_default(), &options)
+}
+
+/// 基于 HEAD 上的 blame 统计代码归属，未指定路径时分析时间窗口内改动过的文件
+#[tauri::command]
+pub async fn get_ownership(
+    path: String,
+    from: i64,
+    to: i64,
+    filter: Option<CommitFilter>,
+    options: Option<OwnershipOptions>,
+    app: AppHandle,
+) -> Result<OwnershipSummary, String> {
+    let config = StorageService::load_config(&app)?;
+    let git_service = GitService::open_repo_with_config(&path, &config)?;
+    let options = options.unwrap_or_else(|| config.ownership.unwrap_or_default());
+    git_service.get_ownership(from, to, &filter.unwrap_or_default(), &options)
+}
+
 #[tauri::command]
-pub async fn get_repo_stats(path: String, from: i64, to: i64) -> Result<RepoStats, String> {
+pub async fn get_releases(path: String, from: i64, to: i64) -> Result<Vec<Release>, String> {
     let git_service = GitService::open_repo(&path)?;
-    let commits = git_service.get_commits(from, to)?;
-    git_service.get_stats(&commits)
+    git_service.get_releases(from, to)
+}
+
+#[tauri::command]
+pub async fn get_submodule_groups(
+    path: String,
+    from: i64,
+    to: i64,
+    filter: Option<CommitFilter>,
+    app: AppHandle,
+) -> Result<Vec<RepoGroup>, String> {
+    let config = StorageService::load_config(&app)?;
+    let git_service = GitService::open_repo_with_config(&path, &config)?;
+    git_service.get_submodule_groups(from, to, &filter.unwrap_or_default())
+}
+
+/// 并发采集多个仓库，每个仓库结束时推送 repo-collection-progress 事件
+#[tauri::command]
+pub async fn collect_repositories(
+    paths: Vec<String>,
+    from: i64,
+    to: i64,
+    filter: Option<CommitFilter>,
+    concurrency: Option<usize>,
+    app: AppHandle,
+) -> Result<CollectResult, String> {
+    let config = StorageService::load_config(&app)?;
+
+    Ok(CollectService::collect_repositories(
+        paths,
+        from,
+        to,
+        filter.unwrap_or_default(),
+        config,
+        concurrency.unwrap_or(DEFAULT_CONCURRENCY),
+        |progress| {
+            let _ = app.emit("repo-collection-progress", progress);
+        },
+    )
+    .await)
 }
 
 // 缓存管理命令（仅 LLM）
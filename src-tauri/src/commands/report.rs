@@ -1,27 +1,45 @@
 // 报告相关 Tauri 命令
 
-use crate::models::{RepoGroup, Report};
+use crate::models::{MergePolicy, RepoGroup, Report, SessionOptions, WorkEstimate};
+use crate::services::session_estimator::SessionEstimator;
+use crate::services::time_zone::TimeZone;
 use crate::services::{
     llm_service::LLMService, report_service::ReportService, storage_service::StorageService,
+    CollectService, GitService,
 };
 use std::sync::Arc;
 use tauri::AppHandle;
 
+/// 流式生成周报；from / to 为用户选择的时间窗口，用于判断浅克隆的历史是否完整
 #[tauri::command]
 pub async fn generate_weekly_report(
     repo_groups: Vec<RepoGroup>,
     template_id: Option<String>,
+    merge_policy: Option<MergePolicy>,
+    from: Option<i64>,
+    to: Option<i64>,
     app: AppHandle,
 ) -> Result<Report, String> {
     // 加载配置
     let config = StorageService::load_config(&app)?;
 
+    // 按合并策略整理前端传入的提交
+    let repo_groups = apply_merge_policy(repo_groups, merge_policy.unwrap_or_default())?;
+    let repo_groups = CollectService::attach_repo_details(repo_groups, &config, from.zip(to));
+
     // 创建服务实例（M5：传递 proxy_config）
     let llm_service = Arc::new(LLMService::new(
         config.llm_provider,
         Some(config.proxy_config),
     ));
-    let report_service = ReportService::new(llm_service);
+    let report_service = ReportService::new(llm_service)
+        .with_diff_inclusion(config.diff_inclusion)
+        .with_hotspot_options(config.hotspots)
+        .with_language_mappings(&config.language_mappings)?
+        .with_timezone(&config.timezone)
+        .with_session_options(config.sessions)
+        .with_ownership(config.ownership)
+        .with_author_aliases(config.author_aliases);
 
     // 以流式方式生成报告
     report_service
@@ -29,21 +47,36 @@ pub async fn generate_weekly_report(
         .await
 }
 
+/// 流式生成月报；from / to 为用户选择的时间窗口，用于判断浅克隆的历史是否完整
 #[tauri::command]
 pub async fn generate_monthly_report(
     repo_groups: Vec<RepoGroup>,
     template_id: Option<String>,
+    merge_policy: Option<MergePolicy>,
+    from: Option<i64>,
+    to: Option<i64>,
     app: AppHandle,
 ) -> Result<Report, String> {
     // 加载配置
     let config = StorageService::load_config(&app)?;
 
+    // 按合并策略整理前端传入的提交
+    let repo_groups = apply_merge_policy(repo_groups, merge_policy.unwrap_or_default())?;
+    let repo_groups = CollectService::attach_repo_details(repo_groups, &config, from.zip(to));
+
     // 创建服务实例（M5：传递 proxy_config）
     let llm_service = Arc::new(LLMService::new(
         config.llm_provider,
         Some(config.proxy_config),
     ));
-    let report_service = ReportService::new(llm_service);
+    let report_service = ReportService::new(llm_service)
+        .with_diff_inclusion(config.diff_inclusion)
+        .with_hotspot_options(config.hotspots)
+        .with_language_mappings(&config.language_mappings)?
+        .with_timezone(&config.timezone)
+        .with_session_options(config.sessions)
+        .with_ownership(config.ownership)
+        .with_author_aliases(config.author_aliases);
 
     // 以流式方式生成报告
     report_service
@@ -51,4 +84,41 @@ pub async fn generate_monthly_report(
         .await
 }
 
+/// 按提交时间估算所选提交的工时（按天、仓库与作者汇总）
+#[tauri::command]
+pub async fn estimate_work_hours(
+    repo_groups: Vec<RepoGroup>,
+    merge_policy: Option<MergePolicy>,
+    options: Option<SessionOptions>,
+    app: AppHandle,
+) -> Result<WorkEstimate, String> {
+    let config = StorageService::load_config(&app)?;
+    let repo_groups = apply_merge_policy(repo_groups, merge_policy.unwrap_or_default())?;
+
+    let estimator = SessionEstimator::new(
+        options.unwrap_or(config.sessions),
+        TimeZone::parse_or_utc(&config.timezone).0,
+    );
+    Ok(estimator.estimate(&repo_groups))
+}
+
+/// 逐仓库套用合并策略；默认策略下不打开仓库
+fn apply_merge_policy(
+    repo_groups: Vec<RepoGroup>,
+    policy: MergePolicy,
+) -> Result<Vec<RepoGroup>, String> {
+    if policy == MergePolicy::All {
+        return Ok(repo_groups);
+    }
+
+    repo_groups
+        .into_iter()
+        .map(|mut group| {
+            let git_service = GitService::open_repo(&group.repo_path)?;
+            group.commits = git_service.apply_merge_policy(group.commits, policy)?;
+            Ok(group)
+        })
+        .collect()
+}
+
 // 说明：export_report 命令已在 M4 中迁移至 commands/export.rs
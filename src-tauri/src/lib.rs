@@ -25,9 +25,16 @@ pub fn run() {
             greet,
             // Git 命令
             git::open_repository,
+            git::scan_repositories,
             git::get_commits,
             git::get_commit_diff,
+            git::get_commit_diff_summary,
             git::get_repo_stats,
+            git::get_hotspots,
+            git::get_ownership,
+            git::get_releases,
+            git::get_submodule_groups,
+            git::collect_repositories,
             // 缓存命令（M6，仅 LLM）
             git::get_cache_stats,
             git::clear_llm_cache,
@@ -37,6 +44,7 @@ pub fn run() {
             // 报告相关命令
             report::generate_weekly_report,
             report::generate_monthly_report,
+            report::estimate_work_hours,
             // 导出命令（M4）
             export::export_report,
             export::get_save_path,
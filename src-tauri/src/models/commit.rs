@@ -11,6 +11,262 @@ pub struct Commit {
     pub message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub diff: Option<String>,
+    /// 可到达该提交的分支（多分支采集时用于标注进行中的工作）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub branches: Vec<String>,
+    /// 父提交哈希，多于一个即为合并提交
+    #[serde(default)]
+    pub parents: Vec<String>,
+    /// merges-only 策略下折叠到合并提交之下的被合并提交
+    #[serde(
+        rename = "mergedCommits",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub merged_commits: Vec<Commit>,
+    /// 逐文件变更明细（相对第一父提交，已启用重命名检测）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub files: Option<Vec<FileChange>>,
+    /// Conventional Commits 解析结果（不规范的提交由关键词推断）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conventional: Option<ConventionalCommit>,
+    /// 提交信息中引用的工单 / Issue（按 AppConfig.ticket_patterns 提取，已去重）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<TicketReference>,
+    /// 该提交中的子模块指针更新
+    #[serde(
+        rename = "submoduleUpdates",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub submodule_updates: Vec<SubmoduleUpdate>,
+    /// `Co-authored-by:` 脚注中的合作者（已按 .mailmap 与别名表解析，不含作者本人）
+    #[serde(rename = "coAuthors", default, skip_serializing_if = "Vec::is_empty")]
+    pub co_authors: Vec<CoAuthor>,
+}
+
+impl Commit {
+    /// 是否为合并提交
+    pub fn is_merge(&self) -> bool {
+        self.parents.len() > 1
+    }
+
+    /// 新增行数合计（无文件明细时为 0）
+    pub fn insertions(&self) -> usize {
+        self.files.iter().flatten().map(|f| f.insertions).sum()
+    }
+
+    /// 删除行数合计（无文件明细时为 0）
+    pub fn deletions(&self) -> usize {
+        self.files.iter().flatten().map(|f| f.deletions).sum()
+    }
+
+    /// 作者去重键：优先使用邮箱（忽略大小写），缺失时退回姓名
+    pub fn author_key(&self) -> String {
+        if self.email.is_empty() {
+            self.author.to_lowercase()
+        } else {
+            self.email.to_lowercase()
+        }
+    }
+
+    /// 作者与全部合作者的去重键，结对提交为每位参与者各计一次
+    pub fn participant_keys(&self) -> Vec<String> {
+        std::iter::once(self.author_key())
+            .chain(self.co_authors.iter().map(CoAuthor::key))
+            .collect()
+    }
+}
+
+/// 结对编程的合作者
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoAuthor {
+    pub name: String,
+    pub email: String,
+}
+
+impl CoAuthor {
+    /// 去重键，规则同 Commit::author_key
+    pub fn key(&self) -> String {
+        if self.email.is_empty() {
+            self.name.to_lowercase()
+        } else {
+            self.email.to_lowercase()
+        }
+    }
+}
+
+/// 父仓库提交中的子模块指针更新
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmoduleUpdate {
+    /// 子模块在父仓库中的路径
+    pub path: String,
+    /// 更新前指向的提交（新增子模块时为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// 更新后指向的提交（移除子模块时为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    /// 此次更新带入的子模块提交哈希（子模块对象缺失时为空）
+    #[serde(default)]
+    pub commits: Vec<String>,
+}
+
+/// 发布：指向窗口内提交的标签
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Release {
+    /// 标签名
+    pub name: String,
+    /// 标签最终指向的提交哈希
+    pub target: String,
+    /// 是否为附注标签（轻量标签没有打标人与说明）
+    pub annotated: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tagger: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tagger_email: Option<String>,
+    /// 附注标签取打标时间，轻量标签取目标提交的提交时间
+    pub timestamp: i64,
+    /// 附注说明
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// 语义化版本标签的上一个版本标签
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_tag: Option<String>,
+}
+
+/// 提交信息中的工单引用
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TicketReference {
+    /// 工单标识，如 `PAY-123`、`#456`
+    pub id: String,
+    /// 命中的规则名称，如 `jira`、`github`
+    pub kind: String,
+}
+
+/// Conventional Commits 结构化信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConventionalCommit {
+    /// 类型（feat、fix、refactor 等，推断失败时为 other）
+    #[serde(rename = "type")]
+    pub commit_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// 头部带 `!` 或存在 BREAKING CHANGE 脚注
+    pub breaking: bool,
+    #[serde(
+        rename = "breakingNote",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub breaking_note: Option<String>,
+    pub description: String,
+    /// 为 true 表示类型由关键词推断而非规范头部
+    #[serde(default)]
+    pub inferred: bool,
+}
+
+/// 文件变更类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    Copied,
+    #[serde(rename = "typechange")]
+    TypeChange,
+}
+
+/// 单个文件的变更明细
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileChange {
+    pub path: String,
+    /// 重命名 / 复制前的路径
+    #[serde(rename = "oldPath", default, skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+    pub kind: ChangeKind,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub binary: bool,
+}
+
+impl ChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Added => "added",
+            Self::Modified => "modified",
+            Self::Deleted => "deleted",
+            Self::Renamed => "renamed",
+            Self::Copied => "copied",
+            Self::TypeChange => "typechange",
+        }
+    }
+}
+
+/// 结构化 diff 摘要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffSummary {
+    pub files: Vec<FileDiffSummary>,
+    pub insertions: usize,
+    pub deletions: usize,
+    /// 完整 diff 的字节数
+    pub patch_size: usize,
+    /// 完整 diff 超过阈值、text 为摘要文本时为 true
+    pub summarized: bool,
+    /// 未超过阈值时为完整 diff，否则为摘要文本
+    pub text: String,
+}
+
+/// 单个文件的 diff 摘要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiffSummary {
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+    pub kind: ChangeKind,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub binary: bool,
+    pub hunks: Vec<HunkSummary>,
+    /// 新增的声明（函数、类型等），能识别时才有
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added_symbols: Vec<String>,
+    /// 删除的声明
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed_symbols: Vec<String>,
+}
+
+/// 单个 hunk 的位置与所在代码块
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HunkSummary {
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    /// hunk 头中的函数上下文（如 `fn parse(input: &str)`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+}
+
+impl HunkSummary {
+    /// 统一 diff 格式的 hunk 头
+    pub fn header(&self) -> String {
+        let header = format!(
+            "@@ -{},{} +{},{} @@",
+            self.old_start, self.old_lines, self.new_start, self.new_lines
+        );
+        match &self.context {
+            Some(context) => format!("{} {}", header, context),
+            None => header,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,6 +276,30 @@ pub struct RepoInfo {
     pub branch: String,
     #[serde(rename = "totalCommits")]
     pub total_commits: usize,
+    /// 本地分支列表，供前端选择采集范围
+    #[serde(rename = "localBranches", default)]
+    pub local_branches: Vec<String>,
+    /// HEAD 状态（空仓库、未诞生分支、分离 HEAD）
+    #[serde(default)]
+    pub state: RepoState,
+    /// 是否为浅克隆
+    #[serde(default)]
+    pub shallow: bool,
+}
+
+/// 仓库 HEAD 状态
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum RepoState {
+    /// HEAD 指向有提交的分支
+    #[default]
+    Normal,
+    /// 刚初始化，没有任何提交
+    Empty { branch: String },
+    /// HEAD 指向的分支尚无提交（如 orphan 分支），其他分支可能有提交
+    Unborn { branch: String },
+    /// 分离 HEAD，CI 检出时常见
+    Detached { commit: String },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,4 +311,143 @@ pub struct RepoStats {
     pub files_changed: usize,
     pub insertions: usize,
     pub deletions: usize,
+    /// 按语言 / 文件类型划分的变更占比
+    #[serde(default)]
+    pub languages: Vec<LanguageShare>,
+    /// 提交时间分布
+    #[serde(default)]
+    pub activity: ActivityStats,
+}
+
+/// 提交时间分布（按 AppConfig.timezone 换算为当地时间）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityStats {
+    pub timezone: String,
+    /// 周一至周日的提交数
+    pub by_weekday: Vec<usize>,
+    /// 0~23 时的提交数
+    pub by_hour: Vec<usize>,
+    /// 星期 × 小时的提交数（7 行 24 列，周一在前），供热力图使用
+    pub heatmap: Vec<Vec<usize>>,
+    pub active_days: usize,
+    /// 首个提交日至最后提交日的天数（含首尾）
+    pub span_days: usize,
+    /// 最长连续活跃天数
+    pub longest_streak: usize,
+    /// 工作日 9:00 前或 19:00 后的提交数
+    pub off_hours_commits: usize,
+    pub weekend_commits: usize,
+    /// 按日期升序的每日提交
+    pub days: Vec<DayActivity>,
+}
+
+/// 单日的提交数与首末提交时间
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DayActivity {
+    /// YYYY-MM-DD
+    pub date: String,
+    pub commits: usize,
+    /// HH:MM
+    pub first_time: String,
+    pub last_time: String,
+}
+
+/// 文件类别：源码按语言细分，测试、文档、配置各自单独成组
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileCategory {
+    Source,
+    Test,
+    Docs,
+    Config,
+    Other,
+}
+
+impl FileCategory {
+    /// 非源码类别在占比中显示的名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Source => "Source",
+            Self::Test => "Tests",
+            Self::Docs => "Docs",
+            Self::Config => "Config",
+            Self::Other => "Other",
+        }
+    }
+}
+
+/// 某一语言或文件类别在变更中的占比
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageShare {
+    /// 源码为语言名（如 Rust），其余为类别名（Tests / Docs / Config / Other）
+    pub name: String,
+    pub category: FileCategory,
+    pub files: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+    /// 占全部变更行数的百分比（保留一位小数；只有二进制文件时按文件数计）
+    pub percent: f64,
+}
+
+/// 改动热点：时间窗口内某个文件或目录的提交数、变更行数与作者数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hotspot {
+    pub path: String,
+    pub commits: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+    /// 不同作者数（结对提交的合作者同样计入）
+    pub authors: usize,
+}
+
+impl Hotspot {
+    pub fn lines_changed(&self) -> usize {
+        self.insertions + self.deletions
+    }
+}
+
+/// HEAD 上存活代码的归属（按 blame 结果汇总）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnershipSummary {
+    pub total_lines: usize,
+    pub files_blamed: usize,
+    /// 二进制、过大、无法 blame 或超出时间预算而未分析的文件数
+    pub files_skipped: usize,
+    /// 是否因超出时间预算而提前结束
+    pub truncated: bool,
+    /// 按存活行数从多到少的作者（已按 .mailmap 与别名表归并）
+    pub authors: Vec<OwnerShare>,
+    /// 按顶层目录汇总，仓库根目录下的文件记为 `.`
+    pub directories: Vec<DirectoryOwnership>,
+}
+
+/// 某位作者名下的存活行数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnerShare {
+    pub name: String,
+    pub email: String,
+    pub lines: usize,
+    /// 占所在范围（仓库或目录）行数的百分比，保留一位小数
+    pub percent: f64,
+}
+
+/// 顶层目录的行数与主要作者
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryOwnership {
+    pub path: String,
+    pub lines: usize,
+    /// 行数最多的几位作者
+    pub owners: Vec<OwnerShare>,
+}
+
+/// 按提交数排序的文件与目录热点
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Hotspots {
+    pub files: Vec<Hotspot>,
+    pub directories: Vec<Hotspot>,
 }
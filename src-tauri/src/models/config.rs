@@ -1,5 +1,7 @@
 // 配置相关数据模型
 
+use super::commit::FileCategory;
+use super::filter::{HotspotOptions, OwnershipOptions, SessionOptions};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -155,6 +157,158 @@ impl ProxyConfig {
     }
 }
 
+/// 作者别名：将多个姓名 / 邮箱归并到同一规范身份
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorAlias {
+    /// 规范姓名
+    pub name: String,
+    /// 规范邮箱
+    pub email: String,
+    /// 需要归并的其他姓名或邮箱（忽略大小写）
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+impl AuthorAlias {
+    /// 判断给定姓名 / 邮箱是否属于该身份
+    pub fn matches(&self, name: &str, email: &str) -> bool {
+        (!email.is_empty() && self.email.eq_ignore_ascii_case(email))
+            || self.aliases.iter().any(|alias| {
+                let alias = alias.trim();
+                !alias.is_empty()
+                    && (alias.eq_ignore_ascii_case(email)
+                        || alias.to_lowercase() == name.to_lowercase())
+            })
+    }
+}
+
+/// 工单引用提取规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TicketPattern {
+    /// 规则名称，写入提取结果的 kind 字段
+    pub name: String,
+    /// 正则表达式；含捕获组时取第一个捕获组作为工单标识，否则取整个匹配
+    pub pattern: String,
+    /// 不视为工单的前缀：标识中第一个 `-` 之前的部分命中时丢弃（如 UTF-8 的 UTF）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_prefixes: Vec<String>,
+}
+
+/// 形似 Jira 键的标准、编码与协议名前缀，默认 Jira 规则不视为工单
+const NON_TICKET_PREFIXES: &[&str] = &[
+    "UTF", "UCS", "SHA", "MD", "CRC", "AES", "RSA", "ISO", "IEC", "IEEE", "RFC", "HTTP", "TLS",
+    "SSL", "IPV", "ES", "ECMA", "CVE", "COVID",
+];
+
+impl TicketPattern {
+    fn new(name: &str, pattern: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+            exclude_prefixes: Vec::new(),
+        }
+    }
+
+    /// 默认规则：`Fixes:` 类脚注、Jira 键、GitHub Issue 编号
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::new(
+                "trailer",
+                r"(?mi)^(?:fixes|closes|resolves|refs?)\s*:\s*([^\s,]+)",
+            ),
+            // 编号不以 0 开头；UTF-8、SHA-256 之类的标准名按前缀排除
+            Self {
+                exclude_prefixes: NON_TICKET_PREFIXES.iter().map(|p| p.to_string()).collect(),
+                ..Self::new("jira", r"\b([A-Z][A-Z0-9]+-[1-9]\d*)\b")
+            },
+            Self::new("github", r"(?:^|[\s(\[,])(#\d+)\b"),
+        ]
+    }
+}
+
+fn default_ticket_patterns() -> Vec<TicketPattern> {
+    TicketPattern::defaults()
+}
+
+/// 自定义的语言识别规则，优先于内置的扩展名 / 文件名表
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageMapping {
+    /// 不含 `/` 时匹配文件名（如 `*.vue`、`Justfile`），否则匹配仓库内完整路径
+    pub pattern: String,
+    /// 语言名，仅 category 为源码时使用
+    #[serde(default)]
+    pub language: String,
+    /// 缺省视为源码
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<FileCategory>,
+}
+
+/// 默认摘要阈值：完整 diff 超过 10KB 时改用摘要文本
+pub const DEFAULT_SUMMARY_THRESHOLD: usize = 10 * 1024;
+
+/// 报告提示词附带 diff 的设置（默认关闭）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffInclusion {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 全部 diff 的字符总预算（中英文混排约 2~4 字符 / token）
+    #[serde(default = "DiffInclusion::default_max_chars")]
+    pub max_chars: usize,
+    /// 单个提交的完整 diff 超过该字节数时改用摘要
+    #[serde(default = "DiffInclusion::default_summary_threshold")]
+    pub summary_threshold: usize,
+    /// 不附带 diff 的路径（锁文件、生成代码、第三方代码等），语法同路径过滤
+    #[serde(default = "DiffInclusion::default_exclude_paths")]
+    pub exclude_paths: Vec<String>,
+}
+
+impl DiffInclusion {
+    fn default_max_chars() -> usize {
+        32_000
+    }
+
+    fn default_summary_threshold() -> usize {
+        DEFAULT_SUMMARY_THRESHOLD
+    }
+
+    fn default_exclude_paths() -> Vec<String> {
+        [
+            "**/*.lock",
+            "**/package-lock.json",
+            "**/pnpm-lock.yaml",
+            "**/go.sum",
+            "**/*.min.js",
+            "**/*.min.css",
+            "**/*.map",
+            "**/*.snap",
+            "**/*.pb.go",
+            "**/*_pb2.py",
+            "**/*.generated.*",
+            "**/generated/**",
+            "**/vendor/**",
+            "**/third_party/**",
+            "**/node_modules/**",
+            "**/dist/**",
+        ]
+        .iter()
+        .map(|p| p.to_string())
+        .collect()
+    }
+}
+
+impl Default for DiffInclusion {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_chars: Self::default_max_chars(),
+            summary_threshold: Self::default_summary_threshold(),
+            exclude_paths: Self::default_exclude_paths(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     #[serde(rename = "llm_provider")]
@@ -164,6 +318,27 @@ pub struct AppConfig {
     pub timezone: String,
     #[serde(default)]
     pub proxy_config: ProxyConfig,
+    /// 作者别名表，在仓库 .mailmap 之后生效
+    #[serde(default)]
+    pub author_aliases: Vec<AuthorAlias>,
+    /// 工单引用提取规则，按顺序匹配，同一工单只记录首个命中的规则
+    #[serde(default = "default_ticket_patterns")]
+    pub ticket_patterns: Vec<TicketPattern>,
+    /// 报告提示词附带 diff 的设置
+    #[serde(default)]
+    pub diff_inclusion: DiffInclusion,
+    /// 报告中改动热点的汇总层级与条目数
+    #[serde(default)]
+    pub hotspots: HotspotOptions,
+    /// 语言识别规则，按顺序匹配
+    #[serde(default)]
+    pub language_mappings: Vec<LanguageMapping>,
+    /// 工时估算参数
+    #[serde(default)]
+    pub sessions: SessionOptions,
+    /// 报告附带代码归属分析的设置，缺省时不分析
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ownership: Option<OwnershipOptions>,
 }
 
 impl Default for AppConfig {
@@ -173,6 +348,13 @@ impl Default for AppConfig {
             export_format: ExportFormat::Markdown,
             timezone: "UTC".to_string(),
             proxy_config: ProxyConfig::default(),
+            author_aliases: Vec::new(),
+            ticket_patterns: TicketPattern::defaults(),
+            diff_inclusion: DiffInclusion::default(),
+            hotspots: HotspotOptions::default(),
+            language_mappings: Vec::new(),
+            sessions: SessionOptions::default(),
+            ownership: None,
         }
     }
 }
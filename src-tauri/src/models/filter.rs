@@ -0,0 +1,159 @@
+// 提交过滤相关数据模型
+
+use serde::{Deserialize, Serialize};
+
+/// 作者匹配方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AuthorMatchMode {
+    /// 完全相等
+    #[default]
+    Exact,
+    /// 忽略大小写相等
+    IgnoreCase,
+    /// 通配符匹配（`*`、`?`、`[...]`），忽略大小写
+    Glob,
+}
+
+/// 分支选择方式：决定从哪些引用开始遍历提交
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "kebab-case")]
+pub enum BranchSelection {
+    /// 仅当前 HEAD
+    #[default]
+    Head,
+    /// 指定分支列表（本地分支、远程分支或任意可解析的引用）
+    Named { branches: Vec<String> },
+    /// 全部本地分支
+    AllLocal,
+    /// 匹配通配符的引用；不以 `refs/` 开头时视为本地分支名
+    Glob { pattern: String },
+}
+
+/// 时间窗口过滤所依据的时间
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TimeKey {
+    /// 提交（落地）时间：变基后的工作计入实际合入的那一周
+    #[default]
+    Committer,
+    /// 作者时间：按最初编写时间统计
+    Author,
+}
+
+/// 合并提交处理策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MergePolicy {
+    /// 合并提交与被合并的提交全部保留
+    #[default]
+    All,
+    /// 去掉合并提交，只保留普通提交
+    ExcludeMerges,
+    /// 只沿第一父提交遍历（主线视角）
+    FirstParent,
+    /// 只保留主线上的合并提交，被合并的提交折叠到 merged_commits 中
+    MergesOnly,
+}
+
+/// 提交采集过滤条件，所有字段均可省略
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CommitFilter {
+    /// 作者姓名或邮箱，命中任意一项即保留；为空表示不过滤
+    pub authors: Vec<String>,
+    pub author_match: AuthorMatchMode,
+    pub branches: BranchSelection,
+    pub time_key: TimeKey,
+    pub merge_policy: MergePolicy,
+    /// 路径通配（如 `services/billing/**`），以 `!` 开头表示排除（如 `!**/*.lock`）
+    /// 提交至少触及一个匹配文件才会保留，统计也只计入匹配的文件
+    pub paths: Vec<String>,
+    /// 关联父仓库中的子模块指针更新，并采集已初始化子模块的提交
+    pub include_submodules: bool,
+}
+
+/// 工作区仓库扫描选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScanOptions {
+    /// 最大递归深度，扫描根目录为 0
+    pub max_depth: usize,
+    /// 忽略的目录：按目录名或相对扫描根目录的路径做通配匹配
+    pub ignore: Vec<String>,
+    /// 判断仓库在窗口内是否活跃所依据的分支与时间，应与采集时一致
+    pub branches: BranchSelection,
+    pub time_key: TimeKey,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 4,
+            ignore: vec![
+                "node_modules".to_string(),
+                "target".to_string(),
+                ".*".to_string(),
+            ],
+            branches: BranchSelection::default(),
+            time_key: TimeKey::default(),
+        }
+    }
+}
+
+/// 改动热点分析选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HotspotOptions {
+    /// 目录汇总的层级：1 为顶层目录，2 为二级目录，依此类推
+    pub depth: usize,
+    /// 文件与目录各保留的条目数
+    pub limit: usize,
+}
+
+impl Default for HotspotOptions {
+    fn default() -> Self {
+        Self {
+            depth: 2,
+            limit: 10,
+        }
+    }
+}
+
+/// 工时估算选项（同 git-hours 的算法与默认值）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SessionOptions {
+    /// 同一作者相邻提交间隔不超过该值（分钟）时视为同一工作会话
+    pub max_gap_minutes: u32,
+    /// 每个会话首个提交之前计入的准备时间（分钟）
+    pub first_commit_minutes: u32,
+}
+
+impl Default for SessionOptions {
+    fn default() -> Self {
+        Self {
+            max_gap_minutes: 120,
+            first_commit_minutes: 120,
+        }
+    }
+}
+
+/// 代码归属分析选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OwnershipOptions {
+    /// 分析 HEAD 上匹配的文件（语法同路径过滤）；为空时分析时间窗口内改动过的文件
+    pub paths: Vec<String>,
+    /// 时间预算（毫秒），用完后不再分析剩余文件
+    pub time_budget_ms: u64,
+}
+
+impl Default for OwnershipOptions {
+    fn default() -> Self {
+        Self {
+            paths: Vec::new(),
+            time_budget_ms: 10_000,
+        }
+    }
+}
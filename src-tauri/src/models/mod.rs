@@ -2,10 +2,26 @@
 
 pub mod commit;
 pub mod config;
+pub mod filter;
 pub mod report;
 pub mod template;
 
-pub use commit::{Commit, RepoInfo, RepoStats};
-pub use config::{AppConfig, ExportFormat, LLMConfig, LLMProvider, ProxyConfig};
-pub use report::{RepoGroup, Report, ReportType};
+pub use commit::{
+    ActivityStats, ChangeKind, CoAuthor, Commit, ConventionalCommit, DayActivity, DiffSummary,
+    DirectoryOwnership, FileCategory, FileChange, FileDiffSummary, Hotspot, Hotspots, HunkSummary,
+    LanguageShare, OwnerShare, OwnershipSummary, Release, RepoInfo, RepoState, RepoStats,
+    SubmoduleUpdate, TicketReference,
+};
+pub use config::{
+    AppConfig, AuthorAlias, DiffInclusion, ExportFormat, LLMConfig, LLMProvider, LanguageMapping,
+    ProxyConfig, TicketPattern, DEFAULT_SUMMARY_THRESHOLD,
+};
+pub use filter::{
+    AuthorMatchMode, BranchSelection, CommitFilter, HotspotOptions, MergePolicy, OwnershipOptions,
+    ScanOptions, SessionOptions, TimeKey,
+};
+pub use report::{
+    CollectProgress, CollectResult, EstimatedHours, RepoError, RepoGroup, RepoWarning, Report,
+    ReportType, ScanResult, WorkEstimate,
+};
 pub use template::{ReportTemplate, TemplateType};
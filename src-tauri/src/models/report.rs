@@ -1,6 +1,6 @@
 // 报告相关数据模型
 
-use super::commit::Commit;
+use super::commit::{Commit, Release, RepoInfo};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,6 +20,131 @@ pub struct RepoGroup {
     #[serde(rename = "repo_path")]
     pub repo_path: String,
     pub commits: Vec<Commit>,
+    /// 时间窗口内的发布（标签）
+    #[serde(default)]
+    pub releases: Vec<Release>,
+    /// 已初始化子模块的提交分组（可继续嵌套）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub submodules: Vec<RepoGroup>,
+    /// 采集告警：仓库状态异常时照常生成报告，并在报告中注明
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<RepoWarning>,
+}
+
+impl RepoGroup {
+    /// 自身及全部嵌套子模块分组（先序）
+    pub fn flatten(&self) -> Vec<&RepoGroup> {
+        std::iter::once(self)
+            .chain(self.submodules.iter().flat_map(RepoGroup::flatten))
+            .collect()
+    }
+}
+
+/// 单个仓库的采集告警
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum RepoWarning {
+    /// 仓库没有任何提交
+    Empty,
+    /// 当前分支尚无提交
+    UnbornBranch { branch: String },
+    /// 分离 HEAD：只统计了检出提交可达的历史
+    DetachedHead { commit: String },
+    /// 浅克隆边界晚于窗口起点：边界之前的提交缺失，本期数据不完整
+    ShallowHistory { boundary: i64 },
+    /// 配置的时区无法识别，时间分布已按 UTC 统计
+    UnknownTimezone { name: String },
+    /// 提交索引未能写入缓存目录，本次结果不受影响，下次采集需重新遍历历史
+    IndexNotSaved { message: String },
+    /// 仓库无法打开或采集失败
+    Failed { message: String },
+}
+
+impl RepoWarning {
+    /// 面向报告读者的说明
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Empty => "仓库没有任何提交".to_string(),
+            Self::UnbornBranch { branch } => format!("当前分支 {} 尚无提交", branch),
+            Self::DetachedHead { commit } => {
+                format!("HEAD 处于分离状态（{}），仅统计该提交可达的历史", commit)
+            }
+            Self::ShallowHistory { boundary } => format!(
+                "浅克隆仓库的历史截止于 {}，此前的提交缺失，本期数据不完整",
+                chrono::DateTime::from_timestamp(*boundary, 0)
+                    .map(|dt| dt.format("%Y-%m-%d").to_string())
+                    .unwrap_or_else(|| boundary.to_string())
+            ),
+            Self::UnknownTimezone { name } => {
+                format!("无法识别时区 {}，时间分布已按 UTC 统计", name)
+            }
+            Self::IndexNotSaved { message } => {
+                format!("提交索引未能保存（{}），下次采集会重新遍历历史", message)
+            }
+            Self::Failed { message } => format!("采集失败：{}", message),
+        }
+    }
+
+    /// 是否意味着报告数据不完整（历史缺失或仓库未能采集）
+    pub fn is_incomplete(&self) -> bool {
+        matches!(self, Self::ShallowHistory { .. } | Self::Failed { .. })
+    }
+}
+
+/// 多仓库采集进度：每个仓库采集结束时推送一次
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectProgress {
+    /// 已结束的仓库数（含失败）
+    pub completed: usize,
+    pub total: usize,
+    pub repo_path: String,
+    pub repo_name: String,
+    /// 该仓库采集到的提交数（含子模块）
+    pub commits: usize,
+    /// 失败原因，成功时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl CollectProgress {
+    /// 进度说明，如 `采集仓库 3/12：shop，840 条提交`
+    pub fn describe(&self) -> String {
+        match &self.error {
+            None => format!(
+                "采集仓库 {}/{}：{}，{} 条提交",
+                self.completed, self.total, self.repo_name, self.commits
+            ),
+            Some(error) => format!(
+                "采集仓库 {}/{}：{} 失败：{}",
+                self.completed, self.total, self.repo_name, error
+            ),
+        }
+    }
+}
+
+/// 多仓库采集结果：成功仓库的分组（保持传入顺序）与逐仓库错误
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectResult {
+    pub repo_groups: Vec<RepoGroup>,
+    pub errors: Vec<RepoError>,
+}
+
+/// 工作区扫描结果：窗口内活跃的仓库（按路径排序）与无法读取的仓库
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanResult {
+    pub repos: Vec<RepoInfo>,
+    pub errors: Vec<RepoError>,
+}
+
+/// 单个仓库的采集错误
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoError {
+    pub repo_path: String,
+    pub message: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,4 +156,38 @@ pub struct Report {
     pub generated_at: i64,
     pub content: String,
     pub commits: Vec<Commit>,
+    /// 各仓库的采集告警（形如 `仓库名：说明`）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// 存在历史缺失或采集失败的仓库时为 true
+    #[serde(default)]
+    pub incomplete: bool,
+}
+
+/// 按提交时间估算的工时
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkEstimate {
+    pub total_hours: f64,
+    pub sessions: usize,
+    /// 估算参数：会话间隔上限与首个提交前的准备时间（分钟）
+    pub max_gap_minutes: u32,
+    pub first_commit_minutes: u32,
+    /// 按日期升序（当地日期，YYYY-MM-DD）
+    pub by_day: Vec<EstimatedHours>,
+    /// 按工时从多到少
+    pub by_repo: Vec<EstimatedHours>,
+    pub by_author: Vec<EstimatedHours>,
+}
+
+/// 某一天、仓库或作者的估算工时
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EstimatedHours {
+    pub name: String,
+    /// 保留一位小数
+    pub hours: f64,
+    /// 会话计入首个提交所在的分组
+    pub sessions: usize,
+    pub commits: usize,
 }
@@ -0,0 +1,388 @@
+// 多仓库采集服务：在阻塞线程池上并发打开并遍历仓库
+
+use crate::models::{
+    AppConfig, CollectProgress, CollectResult, CommitFilter, RepoError, RepoGroup, RepoWarning,
+};
+use crate::services::GitService;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::{Id, JoinSet};
+
+/// 默认并发数：遍历以磁盘读取和解压为主，线程过多反而互相争抢
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+pub struct CollectService;
+
+impl CollectService {
+    /// 并发采集多个仓库，每个仓库结束时回调一次进度
+    /// 单个仓库失败不影响其余仓库，失败原因记入 errors
+    pub async fn collect_repositories<F>(
+        paths: Vec<String>,
+        from: i64,
+        to: i64,
+        filter: CommitFilter,
+        config: AppConfig,
+        concurrency: usize,
+        on_progress: F,
+    ) -> CollectResult
+    where
+        F: FnMut(&CollectProgress),
+    {
+        Self::collect_with(paths, concurrency, on_progress, move |path| {
+            Self::collect_repo_group(path, from, to, &filter, &config)
+        })
+        .await
+    }
+
+    /// 在阻塞线程池上并发执行 collect，结果按传入顺序返回
+    /// 任务 panic 或被取消时同样记为该仓库的错误
+    async fn collect_with<F, C>(
+        paths: Vec<String>,
+        concurrency: usize,
+        mut on_progress: F,
+        collect: C,
+    ) -> CollectResult
+    where
+        F: FnMut(&CollectProgress),
+        C: Fn(&str) -> Result<RepoGroup, String> + Send + Sync + 'static,
+    {
+        let total = paths.len();
+        let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+        let collect = Arc::new(collect);
+
+        // 任务 ID -> (传入顺序, 仓库路径)，任务失败时据此找回仓库
+        let mut spawned: HashMap<Id, (usize, String)> = HashMap::new();
+        let mut tasks = JoinSet::new();
+        for (index, path) in paths.into_iter().enumerate() {
+            let semaphore = semaphore.clone();
+            let collect = collect.clone();
+            let repo_path = path.clone();
+
+            let handle = tasks.spawn(async move {
+                // 信号量从不关闭，acquire 不会失败
+                let _permit = semaphore.acquire_owned().await;
+                tokio::task::spawn_blocking(move || collect(&repo_path))
+                    .await
+                    .unwrap_or_else(|e| Err(format!("Repository collection task failed: {}", e)))
+            });
+            spawned.insert(handle.id(), (index, path));
+        }
+
+        let mut finished = Vec::with_capacity(total);
+        while let Some(joined) = tasks.join_next_with_id().await {
+            let (id, result) = match joined {
+                Ok((id, result)) => (id, result),
+                Err(e) => (
+                    e.id(),
+                    Err(format!("Repository collection task failed: {}", e)),
+                ),
+            };
+            let Some((index, path)) = spawned.remove(&id) else {
+                continue;
+            };
+
+            let progress = match &result {
+                Ok(group) => CollectProgress {
+                    completed: finished.len() + 1,
+                    total,
+                    repo_path: path.clone(),
+                    repo_name: group.repo_name.clone(),
+                    commits: group.flatten().iter().map(|g| g.commits.len()).sum(),
+                    error: None,
+                },
+                Err(message) => CollectProgress {
+                    completed: finished.len() + 1,
+                    total,
+                    repo_path: path.clone(),
+                    repo_name: Path::new(&path)
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| path.clone()),
+                    commits: 0,
+                    error: Some(message.clone()),
+                },
+            };
+            on_progress(&progress);
+
+            finished.push((index, path, result));
+        }
+
+        // 按传入顺序返回，报告中的仓库顺序不受完成先后影响
+        finished.sort_by_key(|(index, _, _)| *index);
+
+        let mut collected = CollectResult::default();
+        for (_, repo_path, result) in finished {
+            match result {
+                Ok(group) => collected.repo_groups.push(group),
+                Err(message) => collected.errors.push(RepoError { repo_path, message }),
+            }
+        }
+
+        collected
+    }
+
+    /// 采集单个仓库：窗口内提交、发布、子模块（按过滤条件）与采集告警
+    pub fn collect_repo_group(
+        path: &str,
+        from: i64,
+        to: i64,
+        filter: &CommitFilter,
+        config: &AppConfig,
+    ) -> Result<RepoGroup, String> {
+        let git_service = GitService::open_repo_with_config(path, config)?;
+        let repo_info = git_service.get_repo_info()?;
+        let commits = git_service.get_commits_filtered(from, to, filter)?;
+        let releases = git_service.get_releases(from, to)?;
+        let submodules = if filter.include_submodules {
+            git_service.get_submodule_groups(from, to, filter)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(RepoGroup {
+            repo_id: repo_info.path.clone(),
+            repo_name: repo_info.name,
+            repo_path: repo_info.path,
+            commits,
+            releases,
+            submodules,
+            warnings: git_service.collection_warnings(from)?,
+        })
+    }
+
+    /// 补充前端未携带的仓库信息（含子模块）：采集告警与发布
+    /// 按与采集相同的配置打开仓库，时区、别名、工单规则及相应告警保持一致
+    /// window 为报告请求的时间窗口，未提供时退回所选提交的首尾时间
+    /// 单个仓库出错只记为告警，不中断报告生成
+    pub fn attach_repo_details(
+        repo_groups: Vec<RepoGroup>,
+        config: &AppConfig,
+        window: Option<(i64, i64)>,
+    ) -> Vec<RepoGroup> {
+        repo_groups
+            .into_iter()
+            .map(|mut group| {
+                group.submodules = Self::attach_repo_details(
+                    std::mem::take(&mut group.submodules),
+                    config,
+                    window,
+                );
+                if let Err(message) = Self::fill_repo_details(&mut group, config, window) {
+                    group.warnings.push(RepoWarning::Failed { message });
+                }
+                group
+            })
+            .collect()
+    }
+
+    fn fill_repo_details(
+        group: &mut RepoGroup,
+        config: &AppConfig,
+        window: Option<(i64, i64)>,
+    ) -> Result<(), String> {
+        let git_service = GitService::open_repo_with_config(&group.repo_path, config)?;
+
+        // 浅克隆边界须与请求的窗口比较：采集到的提交本就不早于边界
+        let (from, to) = match window {
+            Some((from, to)) => (Some(from), Some(to)),
+            None => (
+                group.commits.iter().map(|c| c.timestamp).min(),
+                group.commits.iter().map(|c| c.timestamp).max(),
+            ),
+        };
+
+        // 前端传回的告警可能只是其中一部分，逐条补齐且不重复
+        for warning in git_service.collection_warnings(from.unwrap_or(i64::MAX))? {
+            if !group.warnings.contains(&warning) {
+                group.warnings.push(warning);
+            }
+        }
+
+        if group.releases.is_empty() {
+            if let (Some(from), Some(to)) = (from, to) {
+                group.releases = git_service.get_releases(from, to)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{Oid, Repository, Signature, Time};
+    use std::fs;
+
+    const DAY: i64 = 24 * 60 * 60;
+    const FROM: i64 = 1_704_067_200; // 2024-01-01 00:00:00 UTC
+    const TO: i64 = FROM + 7 * DAY - 1;
+
+    /// 在 HEAD 上追加一个空提交
+    fn commit_at(repo: &Repository, message: &str, time: i64) -> Oid {
+        let signature = Signature::new("Alice", "alice@example.com", &Time::new(time, 0)).unwrap();
+        let tree = repo
+            .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+            .unwrap();
+        let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+    }
+
+    fn group(path: &str) -> RepoGroup {
+        RepoGroup {
+            repo_id: path.to_string(),
+            repo_name: "app".to_string(),
+            repo_path: path.to_string(),
+            commits: Vec::new(),
+            releases: Vec::new(),
+            submodules: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_results_keep_input_order_and_record_failed_tasks() {
+        let paths: Vec<String> = ["slow", "missing", "panics", "fast"]
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        let mut progress = Vec::new();
+
+        let collected = CollectService::collect_with(
+            paths,
+            4,
+            |p| progress.push((p.completed, p.repo_path.clone(), p.error.is_some())),
+            |path| match path {
+                "slow" => {
+                    std::thread::sleep(std::time::Duration::from_millis(50));
+                    Ok(group(path))
+                }
+                "missing" => Err("Failed to open repository".to_string()),
+                "panics" => panic!("corrupt object"),
+                _ => Ok(group(path)),
+            },
+        )
+        .await;
+
+        // 完成先后不影响返回顺序
+        let collected_paths: Vec<&str> = collected
+            .repo_groups
+            .iter()
+            .map(|g| g.repo_path.as_str())
+            .collect();
+        assert_eq!(collected_paths, vec!["slow", "fast"]);
+
+        // panic 的任务同样记为该仓库的错误，不会凭空消失
+        assert_eq!(collected.errors.len(), 2);
+        assert_eq!(collected.errors[0].repo_path, "missing");
+        assert_eq!(collected.errors[1].repo_path, "panics");
+        assert!(collected.errors[1]
+            .message
+            .starts_with("Repository collection task failed"));
+
+        // 进度按完成先后上报，每个仓库恰好一次
+        assert_eq!(
+            progress.iter().map(|p| p.0).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4]
+        );
+        let mut reported: Vec<_> = progress.iter().map(|p| (p.1.as_str(), p.2)).collect();
+        reported.sort();
+        assert_eq!(
+            reported,
+            vec![
+                ("fast", false),
+                ("missing", true),
+                ("panics", true),
+                ("slow", false)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shallow_boundary_is_compared_with_requested_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        commit_at(&repo, "first", FROM + DAY);
+        let boundary = commit_at(&repo, "second", FROM + 3 * DAY);
+        commit_at(&repo, "third", FROM + 4 * DAY);
+        // 浅克隆在 .git/shallow 中记录边界提交，其父提交视为缺失
+        fs::write(repo.path().join("shallow"), format!("{}\n", boundary)).unwrap();
+
+        let config = AppConfig::default();
+        let commits =
+            CollectService::collect_repo_group(path, FROM, TO, &CommitFilter::default(), &config)
+                .unwrap()
+                .commits;
+        let expected = vec![RepoWarning::ShallowHistory {
+            boundary: FROM + 3 * DAY,
+        }];
+
+        // 前端只传回所选提交：它们都不早于边界，须按请求的窗口判断
+        let mut selected = group(path);
+        selected.commits = commits;
+        let groups = CollectService::attach_repo_details(vec![selected], &config, Some((FROM, TO)));
+        assert_eq!(groups[0].warnings, expected);
+
+        // 边界挡住了窗口内的全部提交时同样告警
+        let groups =
+            CollectService::attach_repo_details(vec![group(path)], &config, Some((FROM, TO)));
+        assert_eq!(groups[0].warnings, expected);
+
+        // 窗口整体晚于边界时历史完整
+        let groups = CollectService::attach_repo_details(
+            vec![group(path)],
+            &config,
+            Some((FROM + 3 * DAY, TO)),
+        );
+        assert!(groups[0].warnings.is_empty());
+    }
+
+    #[test]
+    fn test_repo_details_use_config_and_keep_existing_warnings() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        let boundary = commit_at(&repo, "first", FROM + DAY);
+        commit_at(&repo, "second", FROM + 2 * DAY);
+        fs::write(repo.path().join("shallow"), format!("{}\n", boundary)).unwrap();
+
+        let config = AppConfig {
+            timezone: "Mars/Olympus".to_string(),
+            ..Default::default()
+        };
+        let unknown_timezone = RepoWarning::UnknownTimezone {
+            name: "Mars/Olympus".to_string(),
+        };
+        let shallow = RepoWarning::ShallowHistory {
+            boundary: FROM + DAY,
+        };
+
+        // 采集时已有的告警保留，其余告警照常补充
+        let mut selected = group(path);
+        selected.warnings = vec![unknown_timezone.clone()];
+        let groups = CollectService::attach_repo_details(vec![selected], &config, Some((FROM, TO)));
+        assert_eq!(
+            groups[0].warnings,
+            vec![unknown_timezone.clone(), shallow.clone()]
+        );
+
+        let groups =
+            CollectService::attach_repo_details(vec![group(path)], &config, Some((FROM, TO)));
+        assert_eq!(groups[0].warnings, vec![shallow, unknown_timezone]);
+    }
+}
@@ -0,0 +1,540 @@
+// 提交索引：按 OID 持久化提交元数据与逐文件变更统计
+//
+// 索引对已记录的分支起点保持祖先闭包：起点可达的每个提交都在索引中。
+// 因此新的遍历只需让 libgit2 走到已索引起点为止，其余历史直接在内存中展开；
+// 逐文件统计按需计算后写入，之后同一提交无需再算 diff。
+//
+// 磁盘上是按行追加的记录：首行为格式版本与浅克隆边界，之后每行一条分支起点、提交或文件统计。
+// 每次保存只追加新增的记录，历史改写清理过索引后才整体重写。
+
+use crate::models::FileChange;
+use git2::Oid;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
+
+/// 索引格式版本；diff 统计口径或磁盘格式变化时递增，旧索引整体失效
+const INDEX_VERSION: u32 = 2;
+
+/// 应用标识，与 tauri.conf.json 中的 identifier 一致
+const APP_IDENTIFIER: &str = "com.hkdev.gitlog-ai-reporter";
+
+/// 默认索引目录：系统缓存目录下的应用目录（即 Tauri 的 app_cache_dir），桌面端与命令行共用
+/// 单元测试不写入用户目录，索引只在内存中使用
+pub fn default_dir() -> Option<PathBuf> {
+    if cfg!(test) {
+        return None;
+    }
+    dirs::cache_dir().map(|dir| dir.join(APP_IDENTIFIER).join("commit-index"))
+}
+
+/// 仓库对应的索引文件：以规范化后的仓库目录路径的哈希命名，不在仓库内写入任何文件
+pub fn index_path(dir: &Path, repo_dir: &Path) -> PathBuf {
+    let canonical = repo_dir
+        .canonicalize()
+        .unwrap_or_else(|_| repo_dir.to_path_buf());
+    let digest = Sha256::digest(canonical.to_string_lossy().as_bytes());
+    let name: String = digest[..16].iter().map(|b| format!("{:02x}", b)).collect();
+    dir.join(format!("{}.jsonl", name))
+}
+
+/// 索引中的提交元数据
+#[derive(Debug, Clone)]
+pub struct IndexedCommit {
+    pub commit_time: i64,
+    pub author_time: i64,
+    pub parents: Vec<Oid>,
+}
+
+#[derive(Debug, Default)]
+pub struct CommitIndex {
+    /// 为 None 时仅在内存中使用
+    path: Option<PathBuf>,
+    /// 浅克隆边界；边界变化会改变提交的父关系
+    shallow: Vec<String>,
+    /// 分支名 -> 已索引的起点
+    refs: BTreeMap<String, Oid>,
+    commits: HashMap<Oid, IndexedCommit>,
+    /// 提交及其全部祖先中最晚的提交或作者时间，用于按窗口截断遍历
+    newest: HashMap<Oid, i64>,
+    files: HashMap<Oid, Vec<FileChange>>,
+    /// 尚未计算 newest 的提交
+    unsettled: Vec<Oid>,
+    /// 上次保存后新增、待追加的提交与文件统计
+    pending_commits: Vec<Oid>,
+    pending_files: Vec<Oid>,
+    refs_changed: bool,
+    /// 需要整体重写（新文件、格式不符或清理过索引）
+    rewrite: bool,
+}
+
+impl CommitIndex {
+    /// 读取索引；文件缺失、版本或浅克隆边界不一致时从空索引开始
+    /// 无法解析的行（如写入中断留下的半行）直接跳过：缺失的提交会让索引不满足闭包，由调用方重建
+    /// path 为 None 时不读写磁盘
+    pub fn load(path: Option<PathBuf>, shallow: Vec<String>) -> Self {
+        let content = path.as_ref().and_then(|path| fs::read_to_string(path).ok());
+        let mut index = Self {
+            path,
+            shallow,
+            rewrite: true,
+            ..Default::default()
+        };
+
+        let Some(content) = content else {
+            return index;
+        };
+        let mut lines = content.lines();
+        let header = lines
+            .next()
+            .and_then(|line| serde_json::from_str::<StoredHeader>(line).ok());
+        if !header.is_some_and(|h| h.version == INDEX_VERSION && h.shallow == index.shallow) {
+            return index;
+        }
+        // 末行不完整时整体重写，否则追加的记录会接在半行之后
+        index.rewrite = !content.ends_with('\n');
+
+        for record in lines.filter_map(|line| serde_json::from_str::<Record>(line).ok()) {
+            match record {
+                Record::Refs(refs) => {
+                    index.refs = refs
+                        .into_iter()
+                        .filter_map(|(name, oid)| Some((name, Oid::from_str(&oid).ok()?)))
+                        .collect();
+                }
+                Record::Commit(commit) => {
+                    let Ok(oid) = Oid::from_str(&commit.id) else {
+                        continue;
+                    };
+                    index.newest.insert(oid, commit.newest);
+                    index.commits.insert(
+                        oid,
+                        IndexedCommit {
+                            commit_time: commit.commit_time,
+                            author_time: commit.author_time,
+                            parents: commit
+                                .parents
+                                .iter()
+                                .filter_map(|p| Oid::from_str(p).ok())
+                                .collect(),
+                        },
+                    );
+                }
+                Record::Files(files) => {
+                    if let Ok(oid) = Oid::from_str(&files.id) {
+                        index.files.insert(oid, files.files);
+                    }
+                }
+            }
+        }
+
+        index
+    }
+
+    /// 写回磁盘：通常只追加上次保存后新增的记录
+    /// 整体重写时先写临时文件再替换，避免并发读取到半截内容；
+    /// 追加时整批记录一次写入，多个进程同时追加至多留下重复记录
+    pub fn save(&mut self) -> Result<(), String> {
+        let Some(path) = self.path.clone() else {
+            return Ok(());
+        };
+        self.settle();
+
+        if self.rewrite || !path.exists() {
+            let mut bytes = to_line(&StoredHeader {
+                version: INDEX_VERSION,
+                shallow: self.shallow.clone(),
+            })?;
+            for oid in self.commits.keys() {
+                bytes.extend(self.commit_line(*oid)?);
+            }
+            for oid in self.files.keys() {
+                bytes.extend(self.files_line(*oid)?);
+            }
+            bytes.extend(self.refs_line()?);
+
+            let dir = path.parent().unwrap_or(Path::new(""));
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create commit index directory: {}", e))?;
+            // 临时文件名唯一，多个进程同时重写同一仓库的索引时互不覆盖
+            let mut tmp = NamedTempFile::new_in(dir)
+                .map_err(|e| format!("Failed to create commit index file: {}", e))?;
+            tmp.write_all(&bytes)
+                .map_err(|e| format!("Failed to write commit index: {}", e))?;
+            tmp.persist(&path)
+                .map_err(|e| format!("Failed to replace commit index: {}", e))?;
+        } else if self.refs_changed
+            || !self.pending_commits.is_empty()
+            || !self.pending_files.is_empty()
+        {
+            let mut bytes = Vec::new();
+            for oid in &self.pending_commits {
+                bytes.extend(self.commit_line(*oid)?);
+            }
+            for oid in &self.pending_files {
+                bytes.extend(self.files_line(*oid)?);
+            }
+            // 分支起点放在最后：读到起点记录时，其祖先的记录已在文件中
+            if self.refs_changed {
+                bytes.extend(self.refs_line()?);
+            }
+
+            OpenOptions::new()
+                .append(true)
+                .open(&path)
+                .and_then(|mut file| file.write_all(&bytes))
+                .map_err(|e| format!("Failed to write commit index: {}", e))?;
+        }
+
+        self.pending_commits.clear();
+        self.pending_files.clear();
+        self.refs_changed = false;
+        self.rewrite = false;
+        Ok(())
+    }
+
+    fn refs_line(&self) -> Result<Vec<u8>, String> {
+        to_line(&Record::Refs(
+            self.refs
+                .iter()
+                .map(|(name, oid)| (name.clone(), oid.to_string()))
+                .collect(),
+        ))
+    }
+
+    fn commit_line(&self, oid: Oid) -> Result<Vec<u8>, String> {
+        let Some(commit) = self.commits.get(&oid) else {
+            return Ok(Vec::new());
+        };
+        to_line(&Record::Commit(StoredCommit {
+            id: oid.to_string(),
+            commit_time: commit.commit_time,
+            author_time: commit.author_time,
+            newest: self.newest.get(&oid).copied().unwrap_or(i64::MAX),
+            parents: commit.parents.iter().map(Oid::to_string).collect(),
+        }))
+    }
+
+    fn files_line(&self, oid: Oid) -> Result<Vec<u8>, String> {
+        let Some(files) = self.files.get(&oid) else {
+            return Ok(Vec::new());
+        };
+        to_line(&Record::Files(StoredFiles {
+            id: oid.to_string(),
+            files: files.clone(),
+        }))
+    }
+
+    pub fn contains(&self, oid: Oid) -> bool {
+        self.commits.contains_key(&oid)
+    }
+
+    pub fn get(&self, oid: Oid) -> Option<&IndexedCommit> {
+        self.commits.get(&oid)
+    }
+
+    pub fn insert(&mut self, oid: Oid, commit: IndexedCommit) {
+        self.commits.insert(oid, commit);
+        self.unsettled.push(oid);
+        self.pending_commits.push(oid);
+    }
+
+    /// 分支上次索引到的起点
+    pub fn ref_tip(&self, name: &str) -> Option<Oid> {
+        self.refs.get(name).copied()
+    }
+
+    /// 全部已索引起点（祖先闭包的根）
+    pub fn indexed_tips(&self) -> Vec<Oid> {
+        self.refs.values().copied().collect()
+    }
+
+    /// 记录分支的新起点；调用方需保证其祖先均已写入索引
+    pub fn set_ref_tip(&mut self, name: &str, oid: Oid) {
+        if self.refs.insert(name.to_string(), oid) != Some(oid) {
+            self.refs_changed = true;
+        }
+    }
+
+    /// 丢弃指向某个起点的全部分支记录（起点对象已不存在时使用）
+    pub fn forget_tip(&mut self, oid: Oid) {
+        self.refs.retain(|_, tip| *tip != oid);
+        self.refs_changed = true;
+    }
+
+    pub fn files(&self, oid: Oid) -> Option<&Vec<FileChange>> {
+        self.files.get(&oid)
+    }
+
+    pub fn set_files(&mut self, oid: Oid, files: Vec<FileChange>) {
+        self.files.insert(oid, files);
+        self.pending_files.push(oid);
+    }
+
+    /// 清空全部内容（索引与仓库不一致时重建）
+    pub fn clear(&mut self) {
+        self.refs.clear();
+        self.commits.clear();
+        self.newest.clear();
+        self.files.clear();
+        self.unsettled.clear();
+        self.rewrite = true;
+    }
+
+    /// 历史改写后清理：只保留当前已记录起点可达的提交
+    pub fn prune(&mut self) {
+        let reachable = match self.reachable(&self.indexed_tips(), false, i64::MIN) {
+            Some(reachable) => reachable,
+            None => {
+                self.clear();
+                return;
+            }
+        };
+
+        self.commits.retain(|oid, _| reachable.contains(oid));
+        self.newest.retain(|oid, _| reachable.contains(oid));
+        self.files.retain(|oid, _| reachable.contains(oid));
+        self.rewrite = true;
+    }
+
+    /// 按拓扑序（子提交先于父提交，同层按提交时间倒序）展开起点可达的提交
+    /// 只展开自身或某个祖先的时间不早于 since 的提交：其余提交连同祖先都落在窗口之前，
+    /// 时钟偏差、变基造成的时间不单调也不会因此漏掉窗口内的提交
+    /// 索引不满足闭包（有父提交缺失）时返回 None
+    pub fn topo_order(&mut self, tips: &[Oid], first_parent: bool, since: i64) -> Option<Vec<Oid>> {
+        self.settle();
+        let reachable = self.reachable(tips, first_parent, since)?;
+
+        // 入度：被多少个展开的子提交引用；截断在外的父提交不参与
+        let mut in_degree: HashMap<Oid, usize> = reachable.iter().map(|oid| (*oid, 0)).collect();
+        for oid in &reachable {
+            for parent in self.parents_of(*oid, first_parent) {
+                if let Some(degree) = in_degree.get_mut(parent) {
+                    *degree += 1;
+                }
+            }
+        }
+
+        let mut ready: BinaryHeap<(i64, Oid)> = in_degree
+            .iter()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(oid, _)| (self.commits[oid].commit_time, *oid))
+            .collect();
+
+        let mut order = Vec::with_capacity(reachable.len());
+        while let Some((_, oid)) = ready.pop() {
+            order.push(oid);
+            for parent in self.parents_of(oid, first_parent) {
+                let Some(degree) = in_degree.get_mut(parent) else {
+                    continue;
+                };
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.push((self.commits[parent].commit_time, *parent));
+                }
+            }
+        }
+
+        Some(order)
+    }
+
+    /// 起点可达、且 newest 不早于 since 的提交
+    fn reachable(&self, tips: &[Oid], first_parent: bool, since: i64) -> Option<HashSet<Oid>> {
+        let mut reachable = HashSet::new();
+        let mut stack: Vec<Oid> = tips.to_vec();
+
+        while let Some(oid) = stack.pop() {
+            if reachable.contains(&oid) {
+                continue;
+            }
+            if !self.commits.contains_key(&oid) {
+                return None;
+            }
+            if self.newest.get(&oid).is_some_and(|newest| *newest < since) {
+                continue;
+            }
+            reachable.insert(oid);
+            stack.extend(self.parents_of(oid, first_parent).copied());
+        }
+
+        Some(reachable)
+    }
+
+    /// 为新增提交计算 newest：先算父提交再算子提交，已算过的提交直接复用
+    fn settle(&mut self) {
+        let mut stack: Vec<(Oid, bool)> =
+            self.unsettled.drain(..).map(|oid| (oid, false)).collect();
+
+        while let Some((oid, expanded)) = stack.pop() {
+            if self.newest.contains_key(&oid) {
+                continue;
+            }
+            let Some(commit) = self.commits.get(&oid) else {
+                continue;
+            };
+
+            if !expanded {
+                stack.push((oid, true));
+                stack.extend(
+                    commit
+                        .parents
+                        .iter()
+                        .filter(|parent| !self.newest.contains_key(parent))
+                        .map(|parent| (*parent, false)),
+                );
+                continue;
+            }
+
+            // 不在索引中的父提交（浅克隆边界之外）不参与计算
+            let newest = commit
+                .parents
+                .iter()
+                .filter_map(|parent| self.newest.get(parent))
+                .fold(commit.commit_time.max(commit.author_time), |newest, t| {
+                    newest.max(*t)
+                });
+            self.newest.insert(oid, newest);
+        }
+    }
+
+    fn parents_of(&self, oid: Oid, first_parent: bool) -> impl Iterator<Item = &Oid> {
+        let limit = if first_parent { 1 } else { usize::MAX };
+        self.commits
+            .get(&oid)
+            .map(|c| c.parents.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .take(limit)
+    }
+}
+
+/// 序列化为一行记录
+fn to_line<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    let mut bytes = serde_json::to_vec(value)
+        .map_err(|e| format!("Failed to serialize commit index: {}", e))?;
+    bytes.push(b'\n');
+    Ok(bytes)
+}
+
+/// 首行：格式版本与浅克隆边界
+#[derive(Serialize, Deserialize)]
+struct StoredHeader {
+    version: u32,
+    shallow: Vec<String>,
+}
+
+/// 之后每行一条记录；字段名尽量短，大仓库的索引文件可达数十 MB
+#[derive(Serialize, Deserialize)]
+enum Record {
+    /// 全部分支起点，后出现的记录覆盖之前的
+    #[serde(rename = "r")]
+    Refs(BTreeMap<String, String>),
+    #[serde(rename = "c")]
+    Commit(StoredCommit),
+    #[serde(rename = "f")]
+    Files(StoredFiles),
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredCommit {
+    id: String,
+    #[serde(rename = "c")]
+    commit_time: i64,
+    #[serde(rename = "a")]
+    author_time: i64,
+    #[serde(rename = "n")]
+    newest: i64,
+    #[serde(rename = "p", default)]
+    parents: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredFiles {
+    id: String,
+    #[serde(rename = "f")]
+    files: Vec<FileChange>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 24 * 60 * 60;
+    const FROM: i64 = 1_704_067_200; // 2024-01-01 00:00:00 UTC
+
+    fn oid(n: u8) -> Oid {
+        Oid::from_bytes(&[n; 20]).unwrap()
+    }
+
+    fn insert(index: &mut CommitIndex, n: u8, time: i64, parents: &[u8]) {
+        index.insert(
+            oid(n),
+            IndexedCommit {
+                commit_time: time,
+                author_time: time,
+                parents: parents.iter().map(|p| oid(*p)).collect(),
+            },
+        );
+    }
+
+    #[test]
+    fn test_topo_order_stops_below_the_window() {
+        // 1 <- 2 <- 3 <- 4 为主线，5 是时钟偏差的提交：提交时间早于窗口，父提交 3 在窗口内
+        let mut index = CommitIndex::default();
+        insert(&mut index, 1, FROM - 30 * DAY, &[]);
+        insert(&mut index, 2, FROM - 20 * DAY, &[1]);
+        insert(&mut index, 3, FROM + DAY, &[2]);
+        insert(&mut index, 4, FROM + 2 * DAY, &[3]);
+        insert(&mut index, 5, FROM - 40 * DAY, &[3]);
+
+        let order = index.topo_order(&[oid(4)], false, FROM).unwrap();
+        assert_eq!(order, vec![oid(4), oid(3)]);
+
+        let order = index.topo_order(&[oid(5)], false, FROM).unwrap();
+        assert_eq!(order, vec![oid(5), oid(3)]);
+
+        let order = index.topo_order(&[oid(4)], false, i64::MIN).unwrap();
+        assert_eq!(order, vec![oid(4), oid(3), oid(2), oid(1)]);
+
+        // 整段历史都早于窗口的起点不展开
+        assert!(index.topo_order(&[oid(2)], false, FROM).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_save_appends_new_records_and_skips_torn_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.jsonl");
+
+        let mut index = CommitIndex::load(Some(path.clone()), Vec::new());
+        insert(&mut index, 1, FROM, &[]);
+        index.set_ref_tip("main", oid(1));
+        index.save().unwrap();
+        let first = fs::read_to_string(&path).unwrap();
+
+        // 新增提交只追加到文件末尾
+        let mut index = CommitIndex::load(Some(path.clone()), Vec::new());
+        insert(&mut index, 2, FROM + DAY, &[1]);
+        index.set_ref_tip("main", oid(2));
+        index.save().unwrap();
+        let second = fs::read_to_string(&path).unwrap();
+        assert!(second.starts_with(&first));
+        assert_eq!(second.lines().count(), first.lines().count() + 2);
+
+        // 写入中断留下的半行被跳过
+        fs::write(&path, format!("{}{{\"c\":{{\"id\"", second)).unwrap();
+        let mut index = CommitIndex::load(Some(path.clone()), Vec::new());
+        assert_eq!(index.ref_tip("main"), Some(oid(2)));
+        assert_eq!(
+            index.topo_order(&[oid(2)], false, i64::MIN).unwrap(),
+            vec![oid(2), oid(1)]
+        );
+
+        // 浅克隆边界变化时整体失效
+        let index = CommitIndex::load(Some(path), vec![oid(1).to_string()]);
+        assert!(!index.contains(oid(1)));
+    }
+}
@@ -0,0 +1,352 @@
+// 提交信息解析：Conventional Commits 头部、关键词推断与工单引用
+
+use crate::models::{ConventionalCommit, TicketPattern, TicketReference};
+use regex::Regex;
+use std::collections::HashSet;
+
+/// 报告中的类型展示顺序，未列出的类型排在最后
+pub const TYPE_ORDER: &[&str] = &[
+    "feat", "fix", "perf", "refactor", "docs", "test", "build", "ci", "style", "chore", "revert",
+    "other",
+];
+
+/// 解析提交信息；不符合规范的提交按关键词推断类型
+pub fn parse_conventional(message: &str) -> ConventionalCommit {
+    let subject = message.lines().next().unwrap_or("").trim();
+    let breaking_note = find_breaking_footer(message);
+
+    if let Some((commit_type, scope, bang, description)) = parse_header(subject) {
+        return ConventionalCommit {
+            commit_type,
+            scope,
+            breaking: bang || breaking_note.is_some(),
+            breaking_note,
+            description,
+            inferred: false,
+        };
+    }
+
+    ConventionalCommit {
+        commit_type: infer_type(subject).to_string(),
+        scope: None,
+        breaking: breaking_note.is_some(),
+        breaking_note,
+        description: subject.to_string(),
+        inferred: true,
+    }
+}
+
+/// 类型的中文展示名
+pub fn type_label(commit_type: &str) -> &str {
+    match commit_type {
+        "feat" => "新功能",
+        "fix" => "Bug 修复",
+        "perf" => "性能优化",
+        "refactor" => "重构",
+        "docs" => "文档",
+        "test" => "测试",
+        "build" => "构建 / 依赖",
+        "ci" => "持续集成",
+        "style" => "代码风格",
+        "chore" => "杂项",
+        "revert" => "回滚",
+        "other" => "其他",
+        custom => custom,
+    }
+}
+
+/// 工单引用提取器：持有编译后的规则
+#[derive(Debug, Clone, Default)]
+pub struct TicketExtractor {
+    rules: Vec<TicketRule>,
+}
+
+#[derive(Debug, Clone)]
+struct TicketRule {
+    kind: String,
+    regex: Regex,
+    exclude_prefixes: Vec<String>,
+}
+
+impl TicketRule {
+    /// UTF-8、SHA-256、ISO-8601、HTTP-2 等按规则配置的前缀排除
+    fn excludes(&self, id: &str) -> bool {
+        id.split_once('-')
+            .is_some_and(|(prefix, _)| self.exclude_prefixes.iter().any(|p| p == prefix))
+    }
+}
+
+impl TicketExtractor {
+    /// 编译规则；任一正则非法即报错，避免静默漏提取
+    pub fn compile(patterns: &[TicketPattern]) -> Result<Self, String> {
+        let rules = patterns
+            .iter()
+            .map(|p| {
+                Regex::new(&p.pattern)
+                    .map(|regex| TicketRule {
+                        kind: p.name.clone(),
+                        regex,
+                        exclude_prefixes: p.exclude_prefixes.clone(),
+                    })
+                    .map_err(|e| format!("Invalid ticket pattern '{}': {}", p.name, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { rules })
+    }
+
+    /// 提取提交信息中的工单引用，按规则顺序去重
+    pub fn extract(&self, message: &str) -> Vec<TicketReference> {
+        let mut seen = HashSet::new();
+        let mut references = Vec::new();
+
+        for rule in &self.rules {
+            for caps in rule.regex.captures_iter(message) {
+                let Some(m) = caps.get(1).or_else(|| caps.get(0)) else {
+                    continue;
+                };
+                let id = m.as_str().trim();
+                if !id.is_empty() && !rule.excludes(id) && seen.insert(id.to_string()) {
+                    references.push(TicketReference {
+                        id: id.to_string(),
+                        kind: rule.kind.clone(),
+                    });
+                }
+            }
+        }
+
+        references
+    }
+}
+
+/// 解析 `Co-authored-by: Name <email>` 脚注，返回 (姓名, 邮箱)
+pub fn parse_co_authors(message: &str) -> Vec<(String, String)> {
+    const TRAILER: &str = "co-authored-by:";
+
+    message
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let head = line.get(..TRAILER.len())?;
+            if !head.eq_ignore_ascii_case(TRAILER) {
+                return None;
+            }
+
+            let value = line[TRAILER.len()..].trim();
+            let (name, email) = match value.split_once('<') {
+                Some((name, rest)) => (name.trim(), rest.trim_end_matches('>').trim()),
+                None => (value, ""),
+            };
+            (!name.is_empty() || !email.is_empty()).then(|| (name.to_string(), email.to_string()))
+        })
+        .collect()
+}
+
+/// 解析 `type(scope)!: description` 形式的头部
+fn parse_header(subject: &str) -> Option<(String, Option<String>, bool, String)> {
+    // 兼容中文全角冒号
+    let (head, description) = subject.split_once([':', '：'])?;
+    let description = description.trim();
+    if description.is_empty() {
+        return None;
+    }
+
+    let (head, bang) = match head.strip_suffix('!') {
+        Some(rest) => (rest, true),
+        None => (head, false),
+    };
+
+    let (commit_type, scope) = match head.split_once('(') {
+        Some((commit_type, rest)) => {
+            let scope = rest.strip_suffix(')')?.trim();
+            if scope.is_empty() || scope.contains(['(', ')']) {
+                return None;
+            }
+            (commit_type, Some(scope.to_string()))
+        }
+        None => (head, None),
+    };
+
+    // 只认可约定的类型，避免把 "Update: ..." 这类普通标题误判为规范提交
+    let commit_type = commit_type.to_ascii_lowercase();
+    if commit_type == "other" || !TYPE_ORDER.contains(&commit_type.as_str()) {
+        return None;
+    }
+
+    Some((commit_type, scope, bang, description.to_string()))
+}
+
+/// 查找 `BREAKING CHANGE:` / `BREAKING-CHANGE:` 脚注
+fn find_breaking_footer(message: &str) -> Option<String> {
+    message.lines().skip(1).find_map(|line| {
+        let line = line.trim();
+        ["BREAKING CHANGE:", "BREAKING-CHANGE:"]
+            .iter()
+            .find_map(|token| line.strip_prefix(token))
+            .map(|note| note.trim().to_string())
+    })
+}
+
+/// 依据标题关键词推断类型；按优先级依次匹配
+fn infer_type(subject: &str) -> &'static str {
+    let lower = subject.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let has_word = |candidates: &[&str]| words.iter().any(|w| candidates.contains(w));
+    let has_text = |candidates: &[&str]| candidates.iter().any(|c| lower.contains(c));
+
+    // 合并提交标题常带分支名（如 feature-fix），需优先识别
+    if lower.starts_with("merge ") || lower.starts_with("合并") {
+        "chore"
+    } else if has_word(&["revert", "reverts", "reverted"]) || has_text(&["回滚", "还原"]) {
+        "revert"
+    } else if has_word(&["fix", "fixes", "fixed", "bug", "bugfix", "hotfix", "patch"])
+        || has_text(&["修复", "修正", "解决"])
+    {
+        "fix"
+    } else if has_word(&["perf", "performance", "optimize", "speed", "faster"])
+        || has_text(&["性能", "提速"])
+    {
+        "perf"
+    } else if has_word(&[
+        "refactor",
+        "refactoring",
+        "cleanup",
+        "restructure",
+        "simplify",
+    ]) || has_text(&["重构", "清理", "优化"])
+    {
+        "refactor"
+    } else if has_word(&["doc", "docs", "readme", "changelog", "comment", "comments"])
+        || has_text(&["文档", "注释"])
+    {
+        "docs"
+    } else if has_word(&["test", "tests", "testing", "spec"]) || has_text(&["测试", "单测"]) {
+        "test"
+    } else if has_word(&[
+        "ci",
+        "build",
+        "deps",
+        "dependency",
+        "dependencies",
+        "bump",
+        "upgrade",
+    ]) || has_text(&["依赖", "构建", "升级"])
+    {
+        "build"
+    } else if has_word(&[
+        "feat",
+        "feature",
+        "add",
+        "adds",
+        "added",
+        "implement",
+        "support",
+        "new",
+    ]) || has_text(&["新增", "添加", "实现", "支持"])
+    {
+        "feat"
+    } else if has_word(&["chore", "release", "version"]) || has_text(&["发布"]) {
+        "chore"
+    } else {
+        "other"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_conventional_header() {
+        let parsed = parse_conventional("feat(billing)!: drop legacy invoices\n\nbody");
+        assert_eq!(parsed.commit_type, "feat");
+        assert_eq!(parsed.scope.as_deref(), Some("billing"));
+        assert!(parsed.breaking);
+        assert!(!parsed.inferred);
+        assert_eq!(parsed.description, "drop legacy invoices");
+    }
+
+    #[test]
+    fn test_breaking_change_footer() {
+        let parsed = parse_conventional("refactor: rename config\n\nBREAKING CHANGE: key renamed");
+        assert!(parsed.breaking);
+        assert_eq!(parsed.breaking_note.as_deref(), Some("key renamed"));
+    }
+
+    #[test]
+    fn test_keyword_fallback() {
+        assert_eq!(
+            parse_conventional("Fix crash on empty repo").commit_type,
+            "fix"
+        );
+        assert_eq!(parse_conventional("新增导出功能").commit_type, "feat");
+        assert_eq!(
+            parse_conventional("Merge pull request #412 from org/bug-fix").commit_type,
+            "chore"
+        );
+        assert_eq!(parse_conventional("wip").commit_type, "other");
+        assert!(parse_conventional("wip").inferred);
+    }
+
+    #[test]
+    fn test_parse_co_authors() {
+        let message = "feat: pair on billing\n\nCo-authored-by: Alice <alice@example.com>\nco-authored-by: Bob";
+        assert_eq!(
+            parse_co_authors(message),
+            vec![
+                ("Alice".to_string(), "alice@example.com".to_string()),
+                ("Bob".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_ticket_references() {
+        let extractor = TicketExtractor::compile(&TicketPattern::defaults()).unwrap();
+        let refs = extractor.extract("fix(pay): PAY-123 retry refunds (#456)\n\nFixes: #789");
+        let ids: Vec<_> = refs
+            .iter()
+            .map(|r| (r.id.as_str(), r.kind.as_str()))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![("#789", "trailer"), ("PAY-123", "jira"), ("#456", "github")]
+        );
+    }
+
+    #[test]
+    fn test_standard_names_are_not_ticket_references() {
+        let extractor = TicketExtractor::compile(&TicketPattern::defaults()).unwrap();
+        let refs = extractor.extract(
+            "fix: decode UTF-8 paths, verify SHA-256 sums\n\nDates use ISO-8601 over HTTP-2; see OPS-0 and OPS-42",
+        );
+        let ids: Vec<_> = refs.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["OPS-42"]);
+
+        // 排除前缀只作用于配置了它的规则
+        let custom = TicketPattern {
+            name: "standard".to_string(),
+            pattern: r"\b((?:RFC|ISO)-\d+)\b".to_string(),
+            exclude_prefixes: Vec::new(),
+        };
+        let extractor =
+            TicketExtractor::compile(&[TicketPattern::defaults()[1].clone(), custom]).unwrap();
+        let refs = extractor.extract("feat: follow RFC-7231 and ISO-8601 for PAY-7");
+        let ids: Vec<_> = refs
+            .iter()
+            .map(|r| (r.kind.as_str(), r.id.as_str()))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                ("jira", "PAY-7"),
+                ("standard", "RFC-7231"),
+                ("standard", "ISO-8601")
+            ]
+        );
+    }
+}
@@ -0,0 +1,230 @@
+// diff 摘要：逐文件统计、hunk 头（含函数上下文）与增删的声明名
+
+use crate::models::{DiffSummary, FileDiffSummary, HunkSummary, DEFAULT_SUMMARY_THRESHOLD};
+use crate::services::git_service::{delta_paths, PathMatcher};
+use regex::Regex;
+use std::collections::BTreeSet;
+use std::sync::OnceLock;
+
+/// 默认阈值，与配置中 summary_threshold 的默认值一致
+pub const DEFAULT_THRESHOLD: usize = DEFAULT_SUMMARY_THRESHOLD;
+
+/// 摘要文本中最多列出的文件数、每个文件的 hunk 数与声明数
+const MAX_FILES: usize = 50;
+const MAX_HUNKS_PER_FILE: usize = 8;
+const MAX_SYMBOLS_PER_FILE: usize = 12;
+
+/// 汇总 diff 中匹配路径规则的文件；完整 diff 不超过阈值时 text 为完整 diff
+pub(crate) fn summarize(
+    diff: &git2::Diff,
+    matcher: &PathMatcher,
+    threshold: usize,
+) -> Result<DiffSummary, String> {
+    let mut files = Vec::new();
+    let mut patch_text = String::new();
+
+    for index in 0..diff.deltas().len() {
+        let Some(delta) = diff.get_delta(index) else {
+            continue;
+        };
+        let (kind, path, old_path) = delta_paths(&delta);
+        if !matcher.matches_paths(&path, old_path.as_deref()) {
+            continue;
+        }
+
+        let mut file = FileDiffSummary {
+            path,
+            old_path,
+            kind,
+            insertions: 0,
+            deletions: 0,
+            binary: true,
+            hunks: Vec::new(),
+            added_symbols: Vec::new(),
+            removed_symbols: Vec::new(),
+        };
+
+        let patch = git2::Patch::from_diff(diff, index)
+            .map_err(|e| format!("Failed to create patch: {}", e))?;
+        if let Some(mut patch) = patch {
+            summarize_patch(&patch, &mut file)?;
+
+            let buf = patch
+                .to_buf()
+                .map_err(|e| format!("Failed to generate diff patch: {}", e))?;
+            patch_text.push_str(&String::from_utf8_lossy(&buf));
+        }
+
+        files.push(file);
+    }
+
+    let mut summary = DiffSummary {
+        insertions: files.iter().map(|f| f.insertions).sum(),
+        deletions: files.iter().map(|f| f.deletions).sum(),
+        patch_size: patch_text.len(),
+        summarized: patch_text.len() > threshold,
+        files,
+        text: String::new(),
+    };
+
+    summary.text = if summary.summarized {
+        render(&summary)
+    } else {
+        patch_text
+    };
+
+    Ok(summary)
+}
+
+/// 填充单个文件的统计、hunk 与增删的声明
+fn summarize_patch(patch: &git2::Patch, file: &mut FileDiffSummary) -> Result<(), String> {
+    file.binary = patch.delta().flags().is_binary();
+
+    let (_, insertions, deletions) = patch
+        .line_stats()
+        .map_err(|e| format!("Failed to get diff stats: {}", e))?;
+    file.insertions = insertions;
+    file.deletions = deletions;
+
+    let mut added = BTreeSet::new();
+    let mut removed = BTreeSet::new();
+
+    for hunk_index in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch
+            .hunk(hunk_index)
+            .map_err(|e| format!("Failed to read diff hunk: {}", e))?;
+
+        file.hunks.push(HunkSummary {
+            old_start: hunk.old_start(),
+            old_lines: hunk.old_lines(),
+            new_start: hunk.new_start(),
+            new_lines: hunk.new_lines(),
+            context: hunk_context(hunk.header()),
+        });
+
+        for line_index in 0..line_count {
+            let line = patch
+                .line_in_hunk(hunk_index, line_index)
+                .map_err(|e| format!("Failed to read diff line: {}", e))?;
+            let target = match line.origin() {
+                '+' => &mut added,
+                '-' => &mut removed,
+                _ => continue,
+            };
+            if let Some(symbol) = declared_symbol(&String::from_utf8_lossy(line.content())) {
+                target.insert(symbol);
+            }
+        }
+    }
+
+    // 两侧都出现的声明是原地修改（如签名调整），不算增删
+    file.added_symbols = added.difference(&removed).cloned().collect();
+    file.removed_symbols = removed.difference(&added).cloned().collect();
+
+    Ok(())
+}
+
+/// hunk 头 `@@ -a,b +c,d @@` 之后的函数上下文
+fn hunk_context(header: &[u8]) -> Option<String> {
+    let header = String::from_utf8_lossy(header);
+    let context = header.splitn(3, "@@").nth(2)?.trim();
+    (!context.is_empty()).then(|| context.to_string())
+}
+
+/// 识别一行代码声明的函数或类型名（覆盖常见语言，识别不了时返回 None）
+fn declared_symbol(line: &str) -> Option<String> {
+    static PATTERNS: OnceLock<Vec<Regex>> = OnceLock::new();
+
+    let patterns = PATTERNS.get_or_init(|| {
+        [
+            // Rust
+            r#"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:(?:async|const|unsafe|extern\s+"[^"]*")\s+)*(?:(?:fn|struct|enum|trait|union|type|mod)\s+|macro_rules!\s*)([A-Za-z_]\w*)"#,
+            // JavaScript / TypeScript
+            r"^\s*(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?(?:async\s+)?(?:function\*?|class|interface|type|enum)\s+([A-Za-z_$][\w$]*)",
+            r"^\s*(?:export\s+)?(?:const|let|var)\s+([A-Za-z_$][\w$]*)\s*=\s*(?:async\s*)?(?:\([^)]*\)|[A-Za-z_$][\w$]*)\s*=>",
+            // Python
+            r"^\s*(?:async\s+)?(?:def|class)\s+([A-Za-z_]\w*)",
+            // Go
+            r"^func\s+(?:\([^)]*\)\s*)?([A-Za-z_]\w*)",
+            r"^type\s+([A-Za-z_]\w*)",
+            // Java / Kotlin / C#
+            r"^\s*(?:(?:public|private|protected|internal|static|final|abstract|sealed|data|open)\s+)*(?:class|interface|enum|record|object)\s+([A-Za-z_]\w*)",
+        ]
+        .iter()
+        .map(|pattern| Regex::new(pattern).expect("invalid symbol pattern"))
+        .collect()
+    });
+
+    patterns
+        .iter()
+        .find_map(|pattern| pattern.captures(line))
+        .and_then(|captures| captures.get(1))
+        .map(|name| name.as_str().to_string())
+}
+
+/// 大提交的摘要文本
+fn render(summary: &DiffSummary) -> String {
+    let mut text = format!(
+        "⚠️ Large commit (summarized): {} files, +{} -{}\n",
+        summary.files.len(),
+        summary.insertions,
+        summary.deletions
+    );
+
+    for file in summary.files.iter().take(MAX_FILES) {
+        let name = match &file.old_path {
+            Some(old_path) => format!("{} → {}", old_path, file.path),
+            None => file.path.clone(),
+        };
+
+        if file.binary {
+            text.push_str(&format!("- {} ({}, binary)\n", name, file.kind.as_str()));
+            continue;
+        }
+
+        text.push_str(&format!(
+            "- {} ({}, +{} -{})\n",
+            name,
+            file.kind.as_str(),
+            file.insertions,
+            file.deletions
+        ));
+        for hunk in file.hunks.iter().take(MAX_HUNKS_PER_FILE) {
+            text.push_str(&format!("  {}\n", hunk.header()));
+        }
+        if file.hunks.len() > MAX_HUNKS_PER_FILE {
+            text.push_str(&format!(
+                "  … {} more hunks\n",
+                file.hunks.len() - MAX_HUNKS_PER_FILE
+            ));
+        }
+        if !file.added_symbols.is_empty() {
+            text.push_str(&format!("  + {}\n", symbol_list(&file.added_symbols)));
+        }
+        if !file.removed_symbols.is_empty() {
+            text.push_str(&format!("  - {}\n", symbol_list(&file.removed_symbols)));
+        }
+    }
+
+    if summary.files.len() > MAX_FILES {
+        text.push_str(&format!(
+            "… {} more files\n",
+            summary.files.len() - MAX_FILES
+        ));
+    }
+
+    text
+}
+
+fn symbol_list(symbols: &[String]) -> String {
+    let mut list = symbols
+        .iter()
+        .take(MAX_SYMBOLS_PER_FILE)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if symbols.len() > MAX_SYMBOLS_PER_FILE {
+        list.push_str(&format!(" … (+{})", symbols.len() - MAX_SYMBOLS_PER_FILE));
+    }
+    list
+}
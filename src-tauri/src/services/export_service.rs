@@ -1,6 +1,6 @@
 // 导出服务：负责将报告输出为多种格式
 
-use crate::models::Report;
+use crate::models::{Commit, Report};
 use std::fs;
 use std::path::Path;
 
@@ -48,11 +48,12 @@ impl ExportService {
         content.push_str("## 📝 提交详情\n\n");
         for commit in &report.commits {
             content.push_str(&format!(
-                "- `{}` {} - {} ({})\n",
+                "- `{}` {} - {} ({}){}\n",
                 &commit.hash[..7.min(commit.hash.len())],
                 commit.message,
                 commit.author,
-                format_timestamp(commit.timestamp)
+                format_timestamp(commit.timestamp),
+                format_file_summary(commit)
             ));
         }
 
@@ -109,6 +110,19 @@ fn format_timestamp(timestamp: i64) -> String {
         .unwrap_or_else(|| "Unknown date".to_string())
 }
 
+/// 生成提交的文件变更概要，如 " · 3 个文件 +42 -7"；无明细时为空
+fn format_file_summary(commit: &Commit) -> String {
+    match &commit.files {
+        Some(files) if !files.is_empty() => format!(
+            " · {} 个文件 +{} -{}",
+            files.len(),
+            commit.insertions(),
+            commit.deletions()
+        ),
+        _ => String::new(),
+    }
+}
+
 /// 将 ReportType 转为中文展示名称
 fn report_type_to_chinese(report_type: &crate::models::ReportType) -> &'static str {
     match report_type {
@@ -0,0 +1,371 @@
+// 文件分类：按扩展名与文件名识别语言，并把测试、文档、配置从源码中单独分出
+
+use crate::models::{FileCategory, FileChange, LanguageMapping, LanguageShare};
+use std::collections::HashMap;
+
+/// 源码扩展名（小写，不含点）-> 语言
+const SOURCE_EXTENSIONS: &[(&str, &str)] = &[
+    ("rs", "Rust"),
+    ("ts", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("mts", "TypeScript"),
+    ("cts", "TypeScript"),
+    ("js", "JavaScript"),
+    ("jsx", "JavaScript"),
+    ("mjs", "JavaScript"),
+    ("cjs", "JavaScript"),
+    ("vue", "Vue"),
+    ("svelte", "Svelte"),
+    ("py", "Python"),
+    ("go", "Go"),
+    ("java", "Java"),
+    ("kt", "Kotlin"),
+    ("kts", "Kotlin"),
+    ("scala", "Scala"),
+    ("swift", "Swift"),
+    ("m", "Objective-C"),
+    ("mm", "Objective-C"),
+    ("c", "C"),
+    ("h", "C"),
+    ("cc", "C++"),
+    ("cpp", "C++"),
+    ("cxx", "C++"),
+    ("hh", "C++"),
+    ("hpp", "C++"),
+    ("cs", "C#"),
+    ("fs", "F#"),
+    ("rb", "Ruby"),
+    ("php", "PHP"),
+    ("dart", "Dart"),
+    ("lua", "Lua"),
+    ("ex", "Elixir"),
+    ("exs", "Elixir"),
+    ("erl", "Erlang"),
+    ("hs", "Haskell"),
+    ("clj", "Clojure"),
+    ("zig", "Zig"),
+    ("sh", "Shell"),
+    ("bash", "Shell"),
+    ("zsh", "Shell"),
+    ("ps1", "PowerShell"),
+    ("sql", "SQL"),
+    ("html", "HTML"),
+    ("htm", "HTML"),
+    ("css", "CSS"),
+    ("scss", "CSS"),
+    ("sass", "CSS"),
+    ("less", "CSS"),
+    ("hbs", "Handlebars"),
+    ("proto", "Protocol Buffers"),
+    ("graphql", "GraphQL"),
+];
+
+/// 配置文件扩展名
+const CONFIG_EXTENSIONS: &[&str] = &[
+    "json",
+    "jsonc",
+    "json5",
+    "yaml",
+    "yml",
+    "toml",
+    "ini",
+    "cfg",
+    "conf",
+    "properties",
+    "xml",
+    "plist",
+    "env",
+    "lock",
+    "gradle",
+];
+
+/// 按文件名识别的配置 / 构建文件
+const CONFIG_FILENAMES: &[&str] = &[
+    "Dockerfile",
+    "Makefile",
+    "CMakeLists.txt",
+    "Gemfile",
+    "Podfile",
+    "Procfile",
+    "Jenkinsfile",
+    "Justfile",
+    "go.mod",
+    "go.sum",
+    "requirements.txt",
+    "requirements-dev.txt",
+    "constraints.txt",
+];
+
+/// 文档扩展名
+const DOC_EXTENSIONS: &[&str] = &["md", "mdx", "markdown", "rst", "adoc", "txt", "org"];
+
+/// 文档文件名前缀（忽略大小写，如 README、LICENSE-MIT），只用于没有源码扩展名的文件
+const DOC_FILENAME_PREFIXES: &[&str] = &[
+    "readme",
+    "changelog",
+    "changes",
+    "license",
+    "licence",
+    "contributing",
+    "authors",
+    "notice",
+];
+
+/// 测试目录名
+const TEST_DIRS: &[&str] = &[
+    "test",
+    "tests",
+    "__tests__",
+    "spec",
+    "specs",
+    "e2e",
+    "testdata",
+    "__mocks__",
+];
+
+/// 文件分类器：持有编译后的自定义规则
+#[derive(Debug, Clone, Default)]
+pub struct FileClassifier {
+    rules: Vec<(glob::Pattern, bool, FileCategory, String)>,
+}
+
+impl FileClassifier {
+    const OPTIONS: glob::MatchOptions = glob::MatchOptions {
+        case_sensitive: true,
+        require_literal_separator: true,
+        require_literal_leading_dot: false,
+    };
+
+    /// 编译自定义规则；任一模式非法即报错
+    pub fn compile(mappings: &[LanguageMapping]) -> Result<Self, String> {
+        let rules = mappings
+            .iter()
+            .map(|m| {
+                let pattern = m.pattern.trim().trim_start_matches("./");
+                glob::Pattern::new(pattern)
+                    .map(|compiled| {
+                        (
+                            compiled,
+                            pattern.contains('/'),
+                            m.category.unwrap_or(FileCategory::Source),
+                            m.language.clone(),
+                        )
+                    })
+                    .map_err(|e| format!("Invalid language pattern '{}': {}", m.pattern, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { rules })
+    }
+
+    /// 文件的类别与占比中的分组名（源码为语言名，其余为类别名）
+    pub fn classify(&self, path: &str) -> (FileCategory, String) {
+        let (dirs, name) = match path.rsplit_once('/') {
+            Some((dirs, name)) => (dirs, name),
+            None => ("", path),
+        };
+
+        // 自定义规则优先，按顺序取第一个命中的
+        for (pattern, full_path, category, language) in &self.rules {
+            let target = if *full_path { path } else { name };
+            if pattern.matches_with(target, Self::OPTIONS) {
+                return bucket(*category, language);
+            }
+        }
+
+        let extension = name
+            .rsplit_once('.')
+            .filter(|(stem, _)| !stem.is_empty())
+            .map(|(_, ext)| ext.to_lowercase())
+            .unwrap_or_default();
+        let lower_name = name.to_lowercase();
+        let in_dir = |names: &[&str]| {
+            dirs.split('/')
+                .any(|dir| names.contains(&dir.to_lowercase().as_str()))
+        };
+        let language = SOURCE_EXTENSIONS
+            .iter()
+            .find(|(ext, _)| *ext == extension)
+            .map(|(_, language)| *language);
+
+        // 固定文件名先于扩展名判断，否则 CMakeLists.txt 等会被 txt 归入文档
+        let category = if CONFIG_FILENAMES.contains(&name) {
+            FileCategory::Config
+        } else if DOC_EXTENSIONS.contains(&extension.as_str())
+            || (language.is_none()
+                && DOC_FILENAME_PREFIXES
+                    .iter()
+                    .any(|prefix| lower_name.starts_with(prefix)))
+        {
+            FileCategory::Docs
+        } else if in_dir(TEST_DIRS) || is_test_file(name, language.is_some()) {
+            FileCategory::Test
+        } else if in_dir(&["docs", "doc"]) {
+            FileCategory::Docs
+        } else if CONFIG_EXTENSIONS.contains(&extension.as_str()) || name.starts_with('.') {
+            FileCategory::Config
+        } else if language.is_some() {
+            FileCategory::Source
+        } else {
+            FileCategory::Other
+        };
+
+        bucket(category, language.unwrap_or_default())
+    }
+
+    /// 按变更行数计算各语言 / 类别的占比，从高到低排列
+    pub fn breakdown<'a>(
+        &self,
+        files: impl IntoIterator<Item = &'a FileChange>,
+    ) -> Vec<LanguageShare> {
+        let mut buckets: HashMap<(FileCategory, String), LanguageShare> = HashMap::new();
+
+        for file in files {
+            let (category, name) = self.classify(&file.path);
+            let share = buckets
+                .entry((category, name.clone()))
+                .or_insert_with(|| LanguageShare {
+                    name,
+                    category,
+                    files: 0,
+                    insertions: 0,
+                    deletions: 0,
+                    percent: 0.0,
+                });
+            share.files += 1;
+            share.insertions += file.insertions;
+            share.deletions += file.deletions;
+        }
+
+        let mut shares: Vec<LanguageShare> = buckets.into_values().collect();
+
+        // 只有二进制文件（没有行数）时按文件数计算
+        let total_lines: usize = shares.iter().map(|s| s.insertions + s.deletions).sum();
+        let weight = |share: &LanguageShare| {
+            if total_lines > 0 {
+                share.insertions + share.deletions
+            } else {
+                share.files
+            }
+        };
+        let total: usize = shares.iter().map(weight).sum();
+
+        for share in &mut shares {
+            if total > 0 {
+                share.percent = (weight(share) as f64 * 1000.0 / total as f64).round() / 10.0;
+            }
+        }
+
+        shares.sort_by(|a, b| {
+            weight(b)
+                .cmp(&weight(a))
+                .then(a.category.cmp(&b.category))
+                .then(a.name.cmp(&b.name))
+        });
+        shares
+    }
+}
+
+/// 分组名：源码按语言分组，其余类别各自一组
+fn bucket(category: FileCategory, language: &str) -> (FileCategory, String) {
+    match category {
+        FileCategory::Source if !language.is_empty() => (category, language.to_string()),
+        FileCategory::Source => (FileCategory::Other, FileCategory::Other.label().to_string()),
+        _ => (category, category.label().to_string()),
+    }
+}
+
+/// 按命名约定识别测试文件：foo_test.go、foo.test.ts、foo.spec.js、test_foo.py、FooTest.java
+fn is_test_file(name: &str, is_source: bool) -> bool {
+    if !is_source {
+        return false;
+    }
+
+    let stem = name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(name);
+    let lower_stem = stem.to_lowercase();
+    lower_stem.starts_with("test_")
+        || ["_test", ".test", ".spec", "_spec"]
+            .iter()
+            .any(|suffix| lower_stem.ends_with(suffix))
+        // 驼峰命名只认大写的 Test，避免误判 attest、latest 之类的名字
+        || (["Test", "Tests"].iter().any(|suffix| stem.ends_with(suffix))
+            && !["Test", "Tests"].contains(&stem))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ChangeKind;
+
+    #[test]
+    fn test_classify_separates_tests_docs_and_config() {
+        let classifier = FileClassifier::default();
+        let name = |path: &str| classifier.classify(path).1;
+
+        assert_eq!(name("src/main.rs"), "Rust");
+        assert_eq!(name("src/App.tsx"), "TypeScript");
+        assert_eq!(name("tests/integration.rs"), "Tests");
+        assert_eq!(name("src/utils.test.ts"), "Tests");
+        assert_eq!(name("pkg/server_test.go"), "Tests");
+        assert_eq!(name("README.md"), "Docs");
+        assert_eq!(name("docs/guide/setup.py"), "Docs");
+        assert_eq!(name("Cargo.toml"), "Config");
+        assert_eq!(name(".gitignore"), "Config");
+        assert_eq!(name("assets/logo.png"), "Other");
+        // 名称中含 test 但不是测试约定
+        assert_eq!(name("src/attest.rs"), "Rust");
+        // 固定文件名先于 txt 文档扩展名
+        assert_eq!(name("CMakeLists.txt"), "Config");
+        assert_eq!(name("requirements.txt"), "Config");
+        assert_eq!(name("notes.txt"), "Docs");
+        // 文档前缀只用于没有源码扩展名的文件
+        assert_eq!(name("LICENSE-MIT"), "Docs");
+        assert_eq!(name("src/authors.rs"), "Rust");
+        assert_eq!(name("src/changes.ts"), "TypeScript");
+        assert_eq!(name("tools/license_check.go"), "Go");
+    }
+
+    #[test]
+    fn test_mappings_override_builtin_table_and_shares_sum_by_lines() {
+        let classifier = FileClassifier::compile(&[
+            LanguageMapping {
+                pattern: "*.hbs".to_string(),
+                language: String::new(),
+                category: Some(FileCategory::Docs),
+            },
+            LanguageMapping {
+                pattern: "scripts/**".to_string(),
+                language: "Tooling".to_string(),
+                category: None,
+            },
+        ])
+        .unwrap();
+        assert_eq!(classifier.classify("src/templates/weekly.hbs").1, "Docs");
+        assert_eq!(classifier.classify("scripts/release.sh").1, "Tooling");
+
+        let file = |path: &str, insertions: usize| FileChange {
+            path: path.to_string(),
+            old_path: None,
+            kind: ChangeKind::Modified,
+            insertions,
+            deletions: 0,
+            binary: false,
+        };
+        let files = [
+            file("src/lib.rs", 60),
+            file("src/git.rs", 10),
+            file("src/main.ts", 20),
+            file("package.json", 10),
+        ];
+        let shares = classifier.breakdown(&files);
+
+        assert_eq!(shares[0].name, "Rust");
+        assert_eq!(shares[0].files, 2);
+        assert_eq!(shares[0].percent, 70.0);
+        assert_eq!(shares[1].name, "TypeScript");
+        assert_eq!(shares[1].percent, 20.0);
+        assert_eq!(shares[2].name, "Config");
+        assert_eq!(shares[2].category, FileCategory::Config);
+        assert_eq!(shares[2].percent, 10.0);
+    }
+}
@@ -1,55 +1,174 @@
 // Git 服务：封装仓库相关操作
 
-use crate::models::{Commit, RepoInfo, RepoStats};
+use crate::models::{
+    ActivityStats, AppConfig, AuthorAlias, AuthorMatchMode, BranchSelection, ChangeKind, CoAuthor,
+    Commit, CommitFilter, DayActivity, DiffSummary, DirectoryOwnership, FileChange, Hotspot,
+    HotspotOptions, Hotspots, LanguageMapping, MergePolicy, OwnerShare, OwnershipOptions,
+    OwnershipSummary, Release, RepoError, RepoGroup, RepoInfo, RepoState, RepoStats, RepoWarning,
+    ScanOptions, ScanResult, SubmoduleUpdate, TicketPattern, TimeKey,
+};
+use crate::services::commit_index::{self, CommitIndex, IndexedCommit};
+use crate::services::commit_parser::{self, TicketExtractor};
+use crate::services::diff_summary;
+use crate::services::file_classifier::FileClassifier;
+use crate::services::time_zone::TimeZone;
+use chrono::{Datelike, NaiveDate, NaiveTime, Timelike};
 use git2::Repository;
-use std::collections::HashSet;
-use std::path::PathBuf;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 工作时间（当地时间的小时区间），工作日此区间以外的提交计为非工作时间
+const WORK_HOURS: Range<u32> = 9..19;
+
+/// 代码归属分析跳过超过该大小的文件（多为生成代码或数据文件）
+const MAX_BLAME_FILE_SIZE: usize = 1024 * 1024;
+
+/// 代码归属中每个目录列出的作者数
+const MAX_DIRECTORY_OWNERS: usize = 3;
 
 pub struct GitService {
     repo_path: PathBuf,
     repository: Repository,
+    identity: IdentityResolver,
+    tickets: TicketExtractor,
+    languages: FileClassifier,
+    /// 时间分布统计使用的时区
+    timezone: TimeZone,
+    /// 无法识别、已退回 UTC 的时区名
+    unknown_timezone: Option<String>,
+    /// 提交索引所在目录，为 None 时索引只在内存中使用
+    index_dir: Option<PathBuf>,
+    /// 提交索引，首次用到时从索引目录加载
+    index: RefCell<Option<CommitIndex>>,
+    /// 最近一次写回索引失败的原因，作为采集告警返回
+    index_error: RefCell<Option<String>>,
 }
 
 impl GitService {
-    /// 打开指定路径的 Git 仓库
+    /// 打开指定路径所在的 Git 仓库（可传入子目录，支持裸仓库与链接工作树）
     /// 注意：所有时间戳均为 UTC（Unix 秒）
     pub fn open_repo(path: &str) -> Result<Self, String> {
-        let repo_path = PathBuf::from(path);
-
-        let repository = Repository::open(&repo_path)
+        let repository = Repository::discover(path)
             .map_err(|e| format!("Failed to open Git repository: {}", e))?;
 
+        // 仓库根目录：普通仓库与工作树取工作目录，裸仓库取仓库目录本身
+        let repo_path: PathBuf = repository
+            .workdir()
+            .unwrap_or_else(|| repository.path())
+            .components()
+            .collect();
+
+        // .mailmap 缺失或解析失败时按无映射处理
+        let mailmap = repository.mailmap().ok();
+
         Ok(Self {
             repo_path,
             repository,
+            identity: IdentityResolver {
+                mailmap,
+                aliases: Vec::new(),
+            },
+            tickets: TicketExtractor::compile(&TicketPattern::defaults())?,
+            languages: FileClassifier::default(),
+            timezone: TimeZone::utc(),
+            unknown_timezone: None,
+            index_dir: commit_index::default_dir(),
+            index: RefCell::new(None),
+            index_error: RefCell::new(None),
         })
     }
 
-    /// 获取仓库信息
+    /// 打开仓库并套用应用配置（作者别名表、工单规则等）
+    pub fn open_repo_with_config(path: &str, config: &AppConfig) -> Result<Self, String> {
+        Self::open_repo(path)?
+            .with_author_aliases(config.author_aliases.clone())
+            .with_ticket_patterns(&config.ticket_patterns)?
+            .with_language_mappings(&config.language_mappings)
+            .map(|service| service.with_timezone(&config.timezone))
+    }
+
+    /// 沿用父仓库的全部应用配置（别名、工单规则、语言映射、时区），用于子模块
+    /// 子模块自己的 .mailmap 仍然生效；新增配置项时需同步到这里
+    fn inherit_settings(mut self, parent: &GitService) -> Self {
+        self.identity.aliases = parent.identity.aliases.clone();
+        self.tickets = parent.tickets.clone();
+        self.languages = parent.languages.clone();
+        self.timezone = parent.timezone.clone();
+        self.unknown_timezone = parent.unknown_timezone.clone();
+        self.index_dir = parent.index_dir.clone();
+        self
+    }
+
+    /// 设置应用级作者别名表，在 .mailmap 之后生效
+    pub fn with_author_aliases(mut self, aliases: Vec<AuthorAlias>) -> Self {
+        self.identity.aliases = aliases;
+        self
+    }
+
+    /// 设置工单引用提取规则，替换默认规则
+    pub fn with_ticket_patterns(mut self, patterns: &[TicketPattern]) -> Result<Self, String> {
+        self.tickets = TicketExtractor::compile(patterns)?;
+        Ok(self)
+    }
+
+    /// 设置语言识别规则，优先于内置的扩展名 / 文件名表
+    pub fn with_language_mappings(mut self, mappings: &[LanguageMapping]) -> Result<Self, String> {
+        self.languages = FileClassifier::compile(mappings)?;
+        Ok(self)
+    }
+
+    /// 设置时间分布统计使用的时区（IANA 名称、UTC 或 +08:00 形式的固定偏移）
+    /// 无法识别时按 UTC 统计，并在采集告警中提示，不影响其他功能
+    pub fn with_timezone(mut self, timezone: &str) -> Self {
+        let (parsed, error) = TimeZone::parse_or_utc(timezone);
+        self.timezone = parsed;
+        self.unknown_timezone = error.map(|_| timezone.trim().to_string());
+        self
+    }
+
+    /// 设置提交索引所在目录（默认为应用缓存目录），None 表示不持久化
+    pub fn with_index_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.index_dir = dir;
+        self
+    }
+
+    /// 获取仓库信息（提交总量按 HEAD 统计）
     pub fn get_repo_info(&self) -> Result<RepoInfo, String> {
-        let head = self
-            .repository
-            .head()
-            .map_err(|e| format!("Failed to get HEAD: {}", e))?;
+        self.get_repo_info_with(&BranchSelection::Head)
+    }
 
-        let branch_name = head.shorthand().unwrap_or("unknown").to_string();
+    /// 获取仓库信息，提交总量按所选分支去重统计
+    pub fn get_repo_info_with(&self, selection: &BranchSelection) -> Result<RepoInfo, String> {
+        let state = self.repo_state()?;
 
-        let repo_name = self
-            .repo_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("unknown")
-            .to_string();
+        let branch_name = match &state {
+            RepoState::Normal => self
+                .repository
+                .head()
+                .ok()
+                .and_then(|head| head.shorthand().map(str::to_string))
+                .unwrap_or_else(|| "unknown".to_string()),
+            RepoState::Empty { branch } | RepoState::Unborn { branch } => branch.clone(),
+            RepoState::Detached { commit } => format!("HEAD detached at {}", commit),
+        };
 
-        // 统计提交总量
+        let repo_name = self.repo_name();
+
+        // 统计提交总量（多个起点共享的历史只计一次）
         let mut revwalk = self
             .repository
             .revwalk()
             .map_err(|e| format!("Failed to create revwalk: {}", e))?;
 
-        revwalk
-            .push_head()
-            .map_err(|e| format!("Failed to push HEAD: {}", e))?;
+        for (_, tip) in self.resolve_branch_tips(selection)? {
+            revwalk
+                .push(tip)
+                .map_err(|e| format!("Failed to push revision: {}", e))?;
+        }
 
         let total_commits = revwalk.count();
 
@@ -58,65 +177,693 @@ impl GitService {
             name: repo_name,
             branch: branch_name,
             total_commits,
+            local_branches: self.list_local_branches()?,
+            state,
+            shallow: self.repository.is_shallow(),
+        })
+    }
+
+    /// 读取 HEAD 状态；空仓库与未诞生分支不视为错误
+    pub fn repo_state(&self) -> Result<RepoState, String> {
+        match self.repository.head() {
+            Ok(head) => {
+                if self.repository.head_detached().unwrap_or(false) {
+                    let commit = head
+                        .target()
+                        .map(|oid| oid.to_string()[..7].to_string())
+                        .unwrap_or_default();
+                    Ok(RepoState::Detached { commit })
+                } else {
+                    Ok(RepoState::Normal)
+                }
+            }
+            Err(e) if e.code() == git2::ErrorCode::UnbornBranch => {
+                let branch = self.unborn_branch_name();
+                if self.repository.is_empty().unwrap_or(false) {
+                    Ok(RepoState::Empty { branch })
+                } else {
+                    Ok(RepoState::Unborn { branch })
+                }
+            }
+            Err(e) => Err(format!("Failed to get HEAD: {}", e)),
+        }
+    }
+
+    /// 采集告警：HEAD 状态异常，或浅克隆边界晚于窗口起点（数据不完整）时返回对应告警
+    pub fn collection_warnings(&self, from: i64) -> Result<Vec<RepoWarning>, String> {
+        let mut warnings = Vec::new();
+
+        match self.repo_state()? {
+            RepoState::Normal => {}
+            RepoState::Empty { .. } => warnings.push(RepoWarning::Empty),
+            RepoState::Unborn { branch } => warnings.push(RepoWarning::UnbornBranch { branch }),
+            RepoState::Detached { commit } => warnings.push(RepoWarning::DetachedHead { commit }),
+        }
+
+        // 边界早于窗口起点时，缺失的历史不影响本期数据
+        if let Some(boundary) = self.shallow_boundary()? {
+            if boundary > from {
+                warnings.push(RepoWarning::ShallowHistory { boundary });
+            }
+        }
+
+        if let Some(name) = &self.unknown_timezone {
+            warnings.push(RepoWarning::UnknownTimezone { name: name.clone() });
+        }
+
+        if let Some(message) = self.index_error.borrow().clone() {
+            warnings.push(RepoWarning::IndexNotSaved { message });
+        }
+
+        Ok(warnings)
+    }
+
+    /// 未诞生分支的名称（HEAD 的符号引用目标）
+    fn unborn_branch_name(&self) -> String {
+        self.repository
+            .find_reference("HEAD")
+            .ok()
+            .and_then(|head| head.symbolic_target().map(str::to_string))
+            .map(|target| {
+                target
+                    .strip_prefix("refs/heads/")
+                    .unwrap_or(&target)
+                    .to_string()
+            })
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+
+    /// 推导仓库展示名
+    /// 普通仓库取工作目录名；链接工作树取主工作树目录名；
+    /// 裸仓库优先取 origin 远程地址中的仓库名，否则去掉目录名的 `.git` 后缀
+    fn repo_name(&self) -> String {
+        let repo = &self.repository;
+
+        let main_dir = if repo.is_worktree() {
+            // 主仓库为裸仓库时公共目录即仓库目录本身
+            let common = self.common_dir();
+            if common.file_name().is_some_and(|n| n == ".git") {
+                common.parent().map(Path::to_path_buf)
+            } else {
+                return self.bare_repo_name(&common);
+            }
+        } else if repo.is_bare() {
+            return self.bare_repo_name(&self.repo_path);
+        } else {
+            Some(self.repo_path.clone())
+        };
+
+        main_dir
+            .as_deref()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string()
+    }
+
+    /// 仓库公共目录（主仓库的 .git 目录或裸仓库目录）
+    /// 链接工作树的私有目录下有 commondir 文件指向它
+    fn common_dir(&self) -> PathBuf {
+        let git_dir = self.repository.path();
+        fs::read_to_string(git_dir.join("commondir"))
+            .ok()
+            .and_then(|rel| git_dir.join(rel.trim()).canonicalize().ok())
+            .unwrap_or_else(|| git_dir.to_path_buf())
+    }
+
+    /// 浅克隆边界：边界提交中最晚的提交时间，非浅克隆返回 None
+    /// 边界提交的父提交未被拉取，早于该时间的历史可能缺失
+    pub fn shallow_boundary(&self) -> Result<Option<i64>, String> {
+        if !self.repository.is_shallow() {
+            return Ok(None);
+        }
+
+        let shallow = fs::read_to_string(self.common_dir().join("shallow"))
+            .map_err(|e| format!("Failed to read shallow file: {}", e))?;
+
+        let mut boundary = None;
+        for line in shallow.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let oid = git2::Oid::from_str(line)
+                .map_err(|e| format!("Invalid shallow entry '{}': {}", line, e))?;
+            // 边界提交本身也可能未拉取（如被删除的分支），跳过即可
+            if let Ok(commit) = self.repository.find_commit(oid) {
+                let time = commit.time().seconds();
+                boundary = Some(boundary.map_or(time, |b: i64| b.max(time)));
+            }
+        }
+
+        Ok(boundary)
+    }
+
+    /// 裸仓库展示名：origin 远程地址 > 目录名（去掉 `.git` 后缀）
+    fn bare_repo_name(&self, dir: &Path) -> String {
+        let from_remote = self
+            .repository
+            .find_remote("origin")
+            .ok()
+            .and_then(|remote| remote.url().map(str::to_string))
+            .and_then(|url| {
+                url.trim_end_matches('/')
+                    .rsplit(['/', ':'])
+                    .next()
+                    .map(|n| n.trim_end_matches(".git").to_string())
+            })
+            .filter(|n| !n.is_empty());
+
+        from_remote.unwrap_or_else(|| {
+            dir.file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| n.trim_end_matches(".git").to_string())
+                .filter(|n| !n.is_empty())
+                .unwrap_or_else(|| "unknown".to_string())
         })
     }
 
+    /// 递归扫描目录下的 Git 仓库，跳过时间窗口内（按 HEAD）没有提交的仓库
+    /// 找到仓库后不再深入其内部；符号链接目录不跟随，避免循环
+    /// 单个仓库无法读取时记入 errors，不影响其余仓库
+    pub fn discover_repositories(
+        root: &str,
+        from: i64,
+        to: i64,
+        options: &ScanOptions,
+    ) -> Result<ScanResult, String> {
+        let root = Path::new(root);
+        if !root.is_dir() {
+            return Err(format!("Scan root is not a directory: {}", root.display()));
+        }
+
+        let ignore = options
+            .ignore
+            .iter()
+            .map(|p| {
+                glob::Pattern::new(p).map_err(|e| format!("Invalid ignore pattern '{}': {}", p, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut candidates = Vec::new();
+        let mut pending = vec![(root.to_path_buf(), 0usize)];
+
+        while let Some((dir, depth)) = pending.pop() {
+            if is_repository_dir(&dir) {
+                candidates.push(dir);
+                continue;
+            }
+            if depth >= options.max_depth {
+                continue;
+            }
+
+            // 无权限读取的目录直接跳过
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                if !entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                    continue;
+                }
+
+                let path = entry.path();
+                let name = entry.file_name().to_string_lossy().to_string();
+                let relative = path.strip_prefix(root).unwrap_or(&path);
+                let ignored = ignore.iter().any(|p| {
+                    p.matches(&name) || p.matches_path_with(relative, PathMatcher::OPTIONS)
+                });
+
+                if !ignored {
+                    pending.push((path, depth + 1));
+                }
+            }
+        }
+
+        candidates.sort();
+
+        let mut scanned = ScanResult::default();
+        for path in candidates {
+            let Some(path_str) = path.to_str() else {
+                continue;
+            };
+            let result = Self::open_repo(path_str).and_then(|service| {
+                match service.has_commits_between(from, to, &options.branches, options.time_key)? {
+                    true => service.get_repo_info().map(Some),
+                    false => Ok(None),
+                }
+            });
+
+            match result {
+                Ok(Some(info)) => scanned.repos.push(info),
+                Ok(None) => {}
+                Err(message) => scanned.errors.push(RepoError {
+                    repo_path: path_str.to_string(),
+                    message,
+                }),
+            }
+        }
+
+        Ok(scanned)
+    }
+
+    /// 所选分支的历史中是否存在按 time_key 落在窗口内的提交
+    /// 与 get_commits_filtered 一样按祖先中最晚的时间截断遍历，时间不单调的历史也不会漏判
+    fn has_commits_between(
+        &self,
+        from: i64,
+        to: i64,
+        branches: &BranchSelection,
+        time_key: TimeKey,
+    ) -> Result<bool, String> {
+        // 空仓库或没有匹配分支的仓库视为不活跃
+        let tips = self.resolve_branch_tips(branches)?;
+        if tips.is_empty() {
+            return Ok(false);
+        }
+
+        let active = self
+            .walk_commits(&tips, false, from)?
+            .iter()
+            .any(|(_, indexed)| {
+                let time = match time_key {
+                    TimeKey::Committer => indexed.commit_time,
+                    TimeKey::Author => indexed.author_time,
+                };
+                time >= from && time <= to
+            });
+        self.save_index();
+
+        Ok(active)
+    }
+
     /// 获取指定时间范围内的提交
     /// from：Unix 时间戳（秒）
     /// to：Unix 时间戳（秒）
     pub fn get_commits(&self, from: i64, to: i64) -> Result<Vec<Commit>, String> {
+        self.get_commits_filtered(from, to, &CommitFilter::default())
+    }
+
+    /// 按过滤条件获取指定时间范围内的提交
+    /// 多个分支可到达的同一提交只保留一份，并在 branches 中记录全部来源分支
+    ///
+    /// 注意：合并、变基或作者时钟偏差都会让历史中的时间并不单调，
+    /// 因此这里只跳过自身及全部祖先都早于 from 的提交，而不是遇到第一个早于 from 的提交就停止
+    pub fn get_commits_filtered(
+        &self,
+        from: i64,
+        to: i64,
+        filter: &CommitFilter,
+    ) -> Result<Vec<Commit>, String> {
+        let author_matcher = AuthorMatcher::new(&filter.authors, filter.author_match)?;
+        let path_matcher = PathMatcher::new(&filter.paths)?;
+        let tips = self.resolve_branch_tips(&filter.branches)?;
+
+        // 第一父提交视角：只沿主线遍历，被合并分支上的提交不会出现
+        let first_parent = matches!(
+            filter.merge_policy,
+            MergePolicy::FirstParent | MergePolicy::MergesOnly
+        );
+
+        // 待传递的分支标记：提交 OID -> 可到达它的分支下标
+        let mut pending: HashMap<git2::Oid, BTreeSet<usize>> = HashMap::new();
+        for (index, (_, tip)) in tips.iter().enumerate() {
+            pending.entry(*tip).or_default().insert(index);
+        }
+
+        let mut commits = Vec::new();
+
+        // 拓扑序保证子提交先于父提交出现，便于向下传递分支标记
+        for (oid, indexed) in self.walk_commits(&tips, first_parent, from)? {
+            let labels = pending.remove(&oid).unwrap_or_default();
+            let parent_limit = if first_parent { 1 } else { usize::MAX };
+            for parent_id in indexed.parents.iter().take(parent_limit) {
+                pending
+                    .entry(*parent_id)
+                    .or_default()
+                    .extend(labels.iter().copied());
+            }
+
+            let is_merge = indexed.parents.len() > 1;
+            match filter.merge_policy {
+                MergePolicy::ExcludeMerges if is_merge => continue,
+                MergePolicy::MergesOnly if !is_merge => continue,
+                _ => {}
+            }
+
+            let commit_time = match filter.time_key {
+                TimeKey::Committer => indexed.commit_time,
+                TimeKey::Author => indexed.author_time,
+            };
+
+            // 依据时间范围过滤
+            if commit_time < from || commit_time > to {
+                continue;
+            }
+
+            // 只有落在时间范围内的提交才需要读取对象本身
+            let commit = self
+                .repository
+                .find_commit(oid)
+                .map_err(|e| format!("Failed to find commit: {}", e))?;
+
+            // 解析为规范身份后再做作者过滤
+            let (author_name, author_email) = self.identity.resolve_author(&commit);
+            // 结对提交：作者或任一合作者匹配即保留
+            let author_matched = author_matcher.matches(&author_name, &author_email)
+                || self
+                    .identity
+                    .resolve_co_authors(&commit, &author_email)
+                    .iter()
+                    .any(|co| author_matcher.matches(&co.name, &co.email));
+            let branches = labels.iter().map(|&i| tips[i].0.clone()).collect();
+
+            if filter.merge_policy == MergePolicy::MergesOnly {
+                // 合并者本人匹配，或被合并的提交中有匹配作者时保留
+                let mut merged_commits = Vec::new();
+                for side_oid in self.merged_side_oids(&commit)? {
+                    let mut side = self.load_commit(side_oid, filter.time_key, Vec::new())?;
+                    if author_matcher.matches_commit(&side) && path_matcher.retain_files(&mut side)
+                    {
+                        merged_commits.push(side);
+                    }
+                }
+
+                let mut merge =
+                    self.build_commit(&commit, commit_time, author_name, author_email, branches)?;
+                let merge_matched = author_matched && path_matcher.retain_files(&mut merge);
+                if !merge_matched && merged_commits.is_empty() {
+                    continue;
+                }
+
+                merge.merged_commits = merged_commits;
+                commits.push(merge);
+                continue;
+            }
+
+            if !author_matched {
+                continue;
+            }
+
+            let mut model =
+                self.build_commit(&commit, commit_time, author_name, author_email, branches)?;
+
+            // 依据路径过滤：未触及匹配文件的提交不计入
+            if !path_matcher.retain_files(&mut model) {
+                continue;
+            }
+
+            commits.push(model);
+        }
+
+        // 按所选时间倒序排列
+        commits.sort_by_key(|c| std::cmp::Reverse(c.timestamp));
+
+        if filter.include_submodules {
+            self.link_submodule_updates(&mut commits)?;
+        }
+
+        self.save_index();
+
+        Ok(commits)
+    }
+
+    /// 按拓扑序（子提交先于父提交）列出起点可达的提交及其元数据
+    /// 只有上次索引之后新增的提交需要 libgit2 遍历，其余历史直接从索引展开
+    /// 自身及全部祖先的提交、作者时间都早于 since 的提交不会列出
+    fn walk_commits(
+        &self,
+        tips: &[(String, git2::Oid)],
+        first_parent: bool,
+        since: i64,
+    ) -> Result<Vec<(git2::Oid, IndexedCommit)>, String> {
+        let roots: Vec<git2::Oid> = tips.iter().map(|(_, tip)| *tip).collect();
+
+        self.with_index(|index| {
+            // 分支起点不是上次起点的后代：发生了强推或历史改写
+            let rewritten = tips.iter().any(|(name, tip)| {
+                index.ref_tip(name).is_some_and(|prev| {
+                    prev != *tip
+                        && !self
+                            .repository
+                            .graph_descendant_of(*tip, prev)
+                            .unwrap_or(false)
+                })
+            });
+
+            self.extend_index(index, tips)?;
+            if rewritten {
+                index.prune();
+            }
+
+            let order = match index.topo_order(&roots, first_parent, since) {
+                Some(order) => order,
+                None => {
+                    // 索引与仓库不一致（如文件被改动过），清空后完整重建
+                    index.clear();
+                    self.extend_index(index, tips)?;
+                    index
+                        .topo_order(&roots, first_parent, since)
+                        .ok_or_else(|| "Failed to walk commit index".to_string())?
+                }
+            };
+
+            Ok(order
+                .into_iter()
+                .filter_map(|oid| index.get(oid).map(|commit| (oid, commit.clone())))
+                .collect())
+        })
+    }
+
+    /// 把起点可达、尚未入索引的提交写入索引，并记录新的起点
+    /// 遍历在已索引的起点处截止，其祖先已在索引中
+    fn extend_index(
+        &self,
+        index: &mut CommitIndex,
+        tips: &[(String, git2::Oid)],
+    ) -> Result<(), String> {
         let mut revwalk = self
             .repository
             .revwalk()
             .map_err(|e| format!("Failed to create revwalk: {}", e))?;
 
-        revwalk
-            .push_head()
-            .map_err(|e| format!("Failed to push HEAD: {}", e))?;
-
-        let mut commits = Vec::new();
+        for (_, tip) in tips {
+            revwalk
+                .push(*tip)
+                .map_err(|e| format!("Failed to push revision: {}", e))?;
+        }
+        for indexed in index.indexed_tips() {
+            // 起点对象已被回收（改写后 gc），不能再作为截止点
+            if revwalk.hide(indexed).is_err() {
+                index.forget_tip(indexed);
+            }
+        }
 
         for oid_result in revwalk {
             let oid = oid_result.map_err(|e| format!("Failed to get OID: {}", e))?;
+            if index.contains(oid) {
+                continue;
+            }
+
             let commit = self
                 .repository
                 .find_commit(oid)
                 .map_err(|e| format!("Failed to find commit: {}", e))?;
+            index.insert(
+                oid,
+                IndexedCommit {
+                    commit_time: commit.time().seconds(),
+                    author_time: commit.author().when().seconds(),
+                    parents: commit.parent_ids().collect(),
+                },
+            );
+        }
 
-            let commit_time = commit.time().seconds();
+        for (name, tip) in tips {
+            index.set_ref_tip(name, *tip);
+        }
 
-            // 依据时间范围过滤
-            if commit_time >= from && commit_time <= to {
-                let author = commit.author();
-
-                commits.push(Commit {
-                    hash: commit.id().to_string(),
-                    author: author.name().unwrap_or("Unknown").to_string(),
-                    email: author.email().unwrap_or("").to_string(),
-                    timestamp: commit_time,
-                    message: commit.message().unwrap_or("").to_string(),
-                    diff: None, // 差异内容加载留到 M3
-                });
-            }
+        Ok(())
+    }
 
-            // 超出时间范围则提前终止
-            if commit_time < from {
-                break;
+    /// 访问提交索引，首次访问时从磁盘加载
+    fn with_index<R>(&self, f: impl FnOnce(&mut CommitIndex) -> R) -> R {
+        let mut index = self.index.borrow_mut();
+        f(index.get_or_insert_with(|| {
+            // 浅克隆边界变化会改变父关系，作为索引指纹的一部分
+            let shallow = fs::read_to_string(self.common_dir().join("shallow"))
+                .map(|s| s.lines().map(|l| l.trim().to_string()).collect())
+                .unwrap_or_default();
+            // 链接工作树与主仓库共用对象库，按公共目录共用同一份索引
+            let path = self
+                .index_dir
+                .as_ref()
+                .map(|dir| commit_index::index_path(dir, &self.common_dir()));
+            CommitIndex::load(path, shallow)
+        }))
+    }
+
+    /// 写回提交索引；索引只用于加速，写入失败（如缓存目录不可写）不影响结果，
+    /// 失败原因记下后在采集告警中返回
+    fn save_index(&self) {
+        if let Some(index) = self.index.borrow_mut().as_mut() {
+            *self.index_error.borrow_mut() = index.save().err();
+        }
+    }
+
+    /// 对已采集的提交列表套用合并策略（用于前端回传的提交）
+    /// 折叠与剔除只在给定列表内部进行，不会引入列表之外的提交
+    pub fn apply_merge_policy(
+        &self,
+        commits: Vec<Commit>,
+        policy: MergePolicy,
+    ) -> Result<Vec<Commit>, String> {
+        if policy == MergePolicy::All {
+            return Ok(commits);
+        }
+
+        // 找出列表中的合并提交，以及它们从非第一父提交带入的提交
+        let mut merges: HashSet<String> = HashSet::new();
+        let mut side_of: HashMap<String, Vec<String>> = HashMap::new();
+        for commit in &commits {
+            let oid = git2::Oid::from_str(&commit.hash)
+                .map_err(|e| format!("Invalid commit hash: {}", e))?;
+            let git_commit = self
+                .repository
+                .find_commit(oid)
+                .map_err(|e| format!("Failed to find commit: {}", e))?;
+
+            if git_commit.parent_count() > 1 {
+                merges.insert(commit.hash.clone());
+                if policy != MergePolicy::ExcludeMerges {
+                    let side = self.merged_side_oids(&git_commit)?;
+                    side_of.insert(
+                        commit.hash.clone(),
+                        side.iter().map(|o| o.to_string()).collect(),
+                    );
+                }
             }
         }
 
-        Ok(commits)
+        let side_commits: HashSet<&String> = side_of.values().flatten().collect();
+
+        match policy {
+            MergePolicy::All => Ok(commits),
+            MergePolicy::ExcludeMerges => Ok(commits
+                .into_iter()
+                .filter(|c| !merges.contains(&c.hash))
+                .collect()),
+            MergePolicy::FirstParent => Ok(commits
+                .into_iter()
+                .filter(|c| !side_commits.contains(&c.hash))
+                .collect()),
+            MergePolicy::MergesOnly => {
+                let by_hash: HashMap<String, Commit> = commits
+                    .iter()
+                    .map(|c| (c.hash.clone(), c.clone()))
+                    .collect();
+
+                Ok(commits
+                    .into_iter()
+                    .filter(|c| merges.contains(&c.hash) && !side_commits.contains(&c.hash))
+                    .map(|mut merge| {
+                        if let Some(side) = side_of.get(&merge.hash) {
+                            let folded: Vec<Commit> = side
+                                .iter()
+                                .filter_map(|hash| by_hash.get(hash).cloned())
+                                .collect();
+                            if !folded.is_empty() {
+                                merge.merged_commits = folded;
+                            }
+                        }
+                        merge
+                    })
+                    .collect())
+            }
+        }
     }
 
-    /// 获取指定提交的 diff
-    pub fn get_commit_diff(&self, hash: &str) -> Result<String, String> {
-        let oid = git2::Oid::from_str(hash).map_err(|e| format!("Invalid commit hash: {}", e))?;
+    /// 合并提交从非第一父提交带入的提交（即被合并分支上的工作）
+    fn merged_side_oids(&self, merge: &git2::Commit) -> Result<Vec<git2::Oid>, String> {
+        let mut revwalk = self
+            .repository
+            .revwalk()
+            .map_err(|e| format!("Failed to create revwalk: {}", e))?;
+
+        revwalk
+            .set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)
+            .map_err(|e| format!("Failed to set revwalk sorting: {}", e))?;
+
+        for parent_id in merge.parent_ids().skip(1) {
+            revwalk
+                .push(parent_id)
+                .map_err(|e| format!("Failed to push revision: {}", e))?;
+        }
+
+        let mainline = merge
+            .parent_id(0)
+            .map_err(|e| format!("Failed to get parent: {}", e))?;
+        revwalk
+            .hide(mainline)
+            .map_err(|e| format!("Failed to hide revision: {}", e))?;
+
+        revwalk
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to get OID: {}", e))
+    }
 
+    /// 按 OID 加载单个提交并转为数据模型
+    fn load_commit(
+        &self,
+        oid: git2::Oid,
+        time_key: TimeKey,
+        branches: Vec<String>,
+    ) -> Result<Commit, String> {
         let commit = self
             .repository
             .find_commit(oid)
             .map_err(|e| format!("Failed to find commit: {}", e))?;
+        let (author_name, author_email) = self.identity.resolve_author(&commit);
+
+        self.build_commit(
+            &commit,
+            commit_time_by(&commit, time_key),
+            author_name,
+            author_email,
+            branches,
+        )
+    }
+
+    /// 将 git2 提交转为数据模型（作者身份需已解析）
+    /// 文件变更明细在此一次性算好，后续统计与导出无需再按哈希取 diff
+    fn build_commit(
+        &self,
+        commit: &git2::Commit,
+        timestamp: i64,
+        author: String,
+        email: String,
+        branches: Vec<String>,
+    ) -> Result<Commit, String> {
+        let message = commit.message().unwrap_or("").to_string();
+        let co_authors = self.identity.resolve_co_authors(commit, &email);
+
+        Ok(Commit {
+            hash: commit.id().to_string(),
+            author,
+            email,
+            timestamp,
+            conventional: Some(commit_parser::parse_conventional(&message)),
+            references: self.tickets.extract(&message),
+            message,
+            diff: None, // 差异内容加载留到 M3
+            branches,
+            parents: commit.parent_ids().map(|id| id.to_string()).collect(),
+            merged_commits: Vec::new(),
+            files: Some(self.file_changes(commit)?),
+            // 子模块指针更新由 link_submodule_updates 按需填充
+            submodule_updates: Vec::new(),
+            co_authors,
+        })
+    }
 
+    /// 计算提交相对第一父提交的 diff（根提交与空树比较）
+    fn diff_against_parent(&self, commit: &git2::Commit) -> Result<git2::Diff<'_>, String> {
         // 获取父提交（若存在）
         let parent = if commit.parent_count() > 0 {
             Some(
@@ -139,158 +886,2241 @@ impl GitService {
             .transpose()
             .map_err(|e| format!("Failed to get parent tree: {}", e))?;
 
-        // 生成 diff
-        let diff = self
-            .repository
+        self.repository
             .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
-            .map_err(|e| format!("Failed to create diff: {}", e))?;
-
-        // 将 diff 转为 patch 文本
-        let mut diff_text = String::new();
-
-        diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
-            let origin = line.origin();
-            let content = std::str::from_utf8(line.content()).unwrap_or("");
+            .map_err(|e| format!("Failed to create diff: {}", e))
+    }
 
-            match origin {
-                '+' | '-' | ' ' => {
-                    diff_text.push(origin);
-                    diff_text.push_str(content);
-                }
-                'F' => {
-                    diff_text.push_str("--- ");
-                    diff_text.push_str(content);
-                }
-                'T' => {
-                    diff_text.push_str("+++ ");
-                    diff_text.push_str(content);
-                }
-                'H' => {
-                    diff_text.push_str("@@ ");
-                    diff_text.push_str(content);
-                }
-                _ => {
-                    diff_text.push_str(content);
-                }
+    /// 逐文件统计提交的变更，优先取提交索引中的结果
+    fn file_changes(&self, commit: &git2::Commit) -> Result<Vec<FileChange>, String> {
+        self.with_index(|index| {
+            if let Some(files) = index.files(commit.id()) {
+                return Ok(files.clone());
             }
 
-            true
+            let files = self.compute_file_changes(commit)?;
+            index.set_files(commit.id(), files.clone());
+            Ok(files)
         })
-        .map_err(|e| format!("Failed to generate diff patch: {}", e))?;
-
-        Ok(diff_text)
     }
 
-    /// 为超过 10KB 的大提交生成 diff 摘要
-    pub fn get_commit_diff_summary(&self, hash: &str) -> Result<String, String> {
-        let diff = self.get_commit_diff(hash)?;
+    /// 提交相对第一父提交的 diff，并启用重命名检测
+    fn diff_with_renames(&self, commit: &git2::Commit) -> Result<git2::Diff<'_>, String> {
+        let mut diff = self.diff_against_parent(commit)?;
 
-        const THRESHOLD: usize = 10 * 1024; // 10KB
+        let mut find_options = git2::DiffFindOptions::new();
+        find_options.renames(true);
+        diff.find_similar(Some(&mut find_options))
+            .map_err(|e| format!("Failed to detect renames: {}", e))?;
 
-        if diff.len() <= THRESHOLD {
-            return Ok(diff);
-        }
+        Ok(diff)
+    }
 
-        // 解析 diff 生成概要
-        let lines: Vec<&str> = diff.lines().collect();
-        let mut files: Vec<String> = Vec::new();
-        let mut insertions = 0;
-        let mut deletions = 0;
+    /// 逐文件统计提交的变更（启用重命名检测）
+    fn compute_file_changes(&self, commit: &git2::Commit) -> Result<Vec<FileChange>, String> {
+        let diff = self.diff_with_renames(commit)?;
+        let mut files = Vec::with_capacity(diff.deltas().len());
 
-        for line in &lines {
-            if line.starts_with("+++") || line.starts_with("---") {
-                if let Some(file) = line.split_whitespace().nth(1) {
-                    if file != "/dev/null" && !files.contains(&file.to_string()) {
-                        files.push(file.to_string());
-                    }
+        for index in 0..diff.deltas().len() {
+            let patch = git2::Patch::from_diff(&diff, index)
+                .map_err(|e| format!("Failed to create patch: {}", e))?;
+            let Some(delta) = diff.get_delta(index) else {
+                continue;
+            };
+
+            let (kind, path, old_path) = delta_paths(&delta);
+
+            // 二进制标记在生成 patch 时才会确定
+            let (insertions, deletions, binary) = match &patch {
+                Some(patch) => {
+                    let (_, insertions, deletions) = patch
+                        .line_stats()
+                        .map_err(|e| format!("Failed to get diff stats: {}", e))?;
+                    (insertions, deletions, patch.delta().flags().is_binary())
                 }
-            } else if line.starts_with('+') && !line.starts_with("+++") {
-                insertions += 1;
-            } else if line.starts_with('-') && !line.starts_with("---") {
-                deletions += 1;
-            }
-        }
+                None => (0, 0, true),
+            };
 
-        let total_files = files.len();
-        let files_shown: Vec<String> = files.into_iter().take(20).collect();
-        let files_truncated = total_files.saturating_sub(20);
+            files.push(FileChange {
+                old_path,
+                path,
+                kind,
+                insertions,
+                deletions,
+                binary,
+            });
+        }
 
-        Ok(format!(
-            "⚠️ Large commit (summarized):\n- Modified files: {} total\n- Shown files: {}\n- Hidden files: {}\n- Changes: +{} -{}\n",
-            total_files,
-            files_shown.join(", "),
-            files_truncated,
-            insertions,
-            deletions
-        ))
+        Ok(files)
     }
 
-    /// 基于提交集合统计仓库数据
-    pub fn get_stats(&self, commits: &[Commit]) -> Result<RepoStats, String> {
-        let mut authors = HashSet::new();
-        let mut files_changed = 0;
-        let mut insertions = 0;
-        let mut deletions = 0;
+    /// 采集已初始化子模块在同一时间窗口内的提交，每个子模块一个分组，嵌套子模块递归收集
+    pub fn get_submodule_groups(
+        &self,
+        from: i64,
+        to: i64,
+        filter: &CommitFilter,
+    ) -> Result<Vec<RepoGroup>, String> {
+        // 子模块通常处于分离 HEAD，分支选择只对父仓库有意义；
+        // 包含路径相对父仓库，不适用于子模块，只保留排除规则
+        let filter = CommitFilter {
+            branches: BranchSelection::Head,
+            paths: filter
+                .paths
+                .iter()
+                .filter(|p| p.trim_start().starts_with('!'))
+                .cloned()
+                .collect(),
+            ..filter.clone()
+        };
 
-        // 收集作者集合
-        for commit in commits {
-            authors.insert(&commit.email);
+        let mut groups = Vec::new();
+        for (_, service) in self.open_submodules()? {
+            let info = service.get_repo_info()?;
+            let commits = service.get_commits_filtered(from, to, &filter)?;
+
+            groups.push(RepoGroup {
+                repo_id: info.path.clone(),
+                repo_name: info.name,
+                repo_path: info.path,
+                commits,
+                releases: service.get_releases(from, to)?,
+                submodules: service.get_submodule_groups(from, to, &filter)?,
+                // 子模块本就固定在某个提交上，分离 HEAD 属正常状态
+                warnings: service
+                    .collection_warnings(from)?
+                    .into_iter()
+                    .filter(|w| !matches!(w, RepoWarning::DetachedHead { .. }))
+                    .collect(),
+            });
         }
 
-        // 逐个提交计算 diff 统计
-        for commit in commits {
+        Ok(groups)
+    }
+
+    /// 为提交标注子模块指针更新，并关联每次更新带入的子模块提交
+    pub fn link_submodule_updates(&self, commits: &mut [Commit]) -> Result<(), String> {
+        let submodules = self.open_submodules()?;
+        if submodules.is_empty() {
+            return Ok(());
+        }
+
+        for commit in commits.iter_mut() {
             let oid = git2::Oid::from_str(&commit.hash)
                 .map_err(|e| format!("Invalid commit hash: {}", e))?;
-
             let git_commit = self
                 .repository
                 .find_commit(oid)
                 .map_err(|e| format!("Failed to find commit: {}", e))?;
+            let tree = git_commit
+                .tree()
+                .map_err(|e| format!("Failed to get tree: {}", e))?;
+            let parent_tree = git_commit.parent(0).ok().and_then(|p| p.tree().ok());
 
-            // 获取父提交（若存在）
-            let parent = if git_commit.parent_count() > 0 {
-                Some(
-                    git_commit
-                        .parent(0)
-                        .map_err(|e| format!("Failed to get parent: {}", e))?,
-                )
-            } else {
-                None
+            commit.submodule_updates.clear();
+            for (path, service) in &submodules {
+                let new = gitlink_at(&tree, path);
+                let old = parent_tree.as_ref().and_then(|t| gitlink_at(t, path));
+                if new == old {
+                    continue;
+                }
+
+                commit.submodule_updates.push(SubmoduleUpdate {
+                    path: path.clone(),
+                    from: old.map(|id| id.to_string()),
+                    to: new.map(|id| id.to_string()),
+                    commits: new
+                        .map(|id| service.commits_between(old, id))
+                        .unwrap_or_default(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 打开已初始化（已检出）的子模块，返回 (子模块路径, 服务实例)
+    /// 子模块沿用父仓库的作者别名与工单规则
+    fn open_submodules(&self) -> Result<Vec<(String, GitService)>, String> {
+        let submodules = self
+            .repository
+            .submodules()
+            .map_err(|e| format!("Failed to list submodules: {}", e))?;
+
+        let mut opened = Vec::new();
+        for submodule in submodules {
+            let path = submodule.path().to_string_lossy().to_string();
+            let dir = self.repo_path.join(submodule.path());
+
+            // 未初始化的子模块目录为空，向上查找会误打开父仓库
+            if !is_repository_dir(&dir) {
+                continue;
+            }
+            let Some(dir) = dir.to_str() else {
+                continue;
             };
 
-            // 获取树对象
-            let tree = git_commit
-                .tree()
-                .map_err(|e| format!("Failed to get commit tree: {}", e))?;
+            opened.push((path, Self::open_repo(dir)?.inherit_settings(self)));
+        }
 
-            let parent_tree = parent
-                .as_ref()
-                .map(|p| p.tree())
-                .transpose()
-                .map_err(|e| format!("Failed to get parent tree: {}", e))?;
+        Ok(opened)
+    }
+
+    /// `from..to` 之间的提交哈希；对象尚未拉取时返回空列表
+    fn commits_between(&self, from: Option<git2::Oid>, to: git2::Oid) -> Vec<String> {
+        let Ok(mut revwalk) = self.repository.revwalk() else {
+            return Vec::new();
+        };
+        if revwalk.push(to).is_err() {
+            return Vec::new();
+        }
+        if let Some(from) = from {
+            // 指针回退或旧对象缺失时 hide 失败，按全部可达提交处理
+            let _ = revwalk.hide(from);
+        }
+
+        revwalk.flatten().map(|oid| oid.to_string()).collect()
+    }
+
+    /// 获取指向时间窗口内提交的标签（附注标签与轻量标签）
+    /// 语义化版本标签会记录上一个版本标签，结果按时间倒序
+    pub fn get_releases(&self, from: i64, to: i64) -> Result<Vec<Release>, String> {
+        let tag_names = self
+            .repository
+            .tag_names(None)
+            .map_err(|e| format!("Failed to list tags: {}", e))?;
+
+        let versions: Vec<(semver::Version, &str)> = tag_names
+            .iter()
+            .flatten()
+            .filter_map(|name| parse_semver_tag(name).map(|v| (v, name)))
+            .collect();
+
+        let mut releases = Vec::new();
+        for name in tag_names.iter().flatten() {
+            let reference = self
+                .repository
+                .find_reference(&format!("refs/tags/{}", name))
+                .map_err(|e| format!("Failed to find tag {}: {}", name, e))?;
+
+            // 指向树或文件对象的标签不视为发布
+            let Ok(target) = reference.peel_to_commit() else {
+                continue;
+            };
+            let commit_time = target.time().seconds();
+            if commit_time < from || commit_time > to {
+                continue;
+            }
+
+            // 轻量标签无法剥离出标签对象
+            let tag = reference.peel_to_tag().ok();
+            let tagger = tag.as_ref().and_then(|t| t.tagger());
+
+            let previous_tag = parse_semver_tag(name).and_then(|version| {
+                versions
+                    .iter()
+                    .filter(|(v, _)| *v < version)
+                    .max_by(|(a, _), (b, _)| a.cmp(b))
+                    .map(|(_, n)| n.to_string())
+            });
+
+            releases.push(Release {
+                name: name.to_string(),
+                target: target.id().to_string(),
+                annotated: tag.is_some(),
+                tagger: tagger.as_ref().and_then(|s| s.name()).map(String::from),
+                tagger_email: tagger.as_ref().and_then(|s| s.email()).map(String::from),
+                timestamp: tagger
+                    .as_ref()
+                    .map(|s| s.when().seconds())
+                    .unwrap_or(commit_time),
+                message: tag
+                    .as_ref()
+                    .and_then(|t| t.message())
+                    .map(|m| m.trim().to_string())
+                    .filter(|m| !m.is_empty()),
+                previous_tag,
+            });
+        }
+
+        releases.sort_by_key(|r| std::cmp::Reverse(r.timestamp));
+
+        Ok(releases)
+    }
 
-            // 计算 diff
-            let diff = self
+    /// 列出全部本地分支名
+    pub fn list_local_branches(&self) -> Result<Vec<String>, String> {
+        let branches = self
+            .repository
+            .branches(Some(git2::BranchType::Local))
+            .map_err(|e| format!("Failed to list branches: {}", e))?;
+
+        let mut names = Vec::new();
+        for branch in branches {
+            let (branch, _) = branch.map_err(|e| format!("Failed to read branch: {}", e))?;
+            if let Ok(Some(name)) = branch.name() {
+                names.push(name.to_string());
+            }
+        }
+        names.sort();
+
+        Ok(names)
+    }
+
+    /// 将分支选择解析为 (分支名, 起点提交) 列表
+    fn resolve_branch_tips(
+        &self,
+        selection: &BranchSelection,
+    ) -> Result<Vec<(String, git2::Oid)>, String> {
+        let mut tips = Vec::new();
+
+        match selection {
+            BranchSelection::Head => {
+                // 空仓库或未诞生分支没有起点，按无提交处理
+                let head = match self.repository.head() {
+                    Ok(head) => head,
+                    Err(e) if e.code() == git2::ErrorCode::UnbornBranch => return Ok(tips),
+                    Err(e) => return Err(format!("Failed to get HEAD: {}", e)),
+                };
+                let commit = head
+                    .peel_to_commit()
+                    .map_err(|e| format!("Failed to resolve HEAD: {}", e))?;
+                tips.push((head.shorthand().unwrap_or("HEAD").to_string(), commit.id()));
+            }
+            BranchSelection::Named { branches } => {
+                for name in branches {
+                    let object = self
+                        .repository
+                        .revparse_single(name)
+                        .map_err(|e| format!("Failed to resolve branch '{}': {}", name, e))?;
+                    let commit = object
+                        .peel_to_commit()
+                        .map_err(|e| format!("'{}' does not point to a commit: {}", name, e))?;
+                    tips.push((name.clone(), commit.id()));
+                }
+            }
+            BranchSelection::AllLocal => {
+                let branches = self
+                    .repository
+                    .branches(Some(git2::BranchType::Local))
+                    .map_err(|e| format!("Failed to list branches: {}", e))?;
+
+                for branch in branches {
+                    let (branch, _) =
+                        branch.map_err(|e| format!("Failed to read branch: {}", e))?;
+                    let name = branch
+                        .name()
+                        .ok()
+                        .flatten()
+                        .unwrap_or("unknown")
+                        .to_string();
+                    // 跳过无法解析到提交的分支（如损坏的引用）
+                    if let Ok(commit) = branch.get().peel_to_commit() {
+                        tips.push((name, commit.id()));
+                    }
+                }
+            }
+            BranchSelection::Glob { pattern } => {
+                let full_pattern = if pattern.starts_with("refs/") {
+                    pattern.clone()
+                } else {
+                    format!("refs/heads/{}", pattern)
+                };
+
+                let references = self
+                    .repository
+                    .references_glob(&full_pattern)
+                    .map_err(|e| format!("Invalid branch pattern '{}': {}", pattern, e))?;
+
+                for reference in references {
+                    let reference =
+                        reference.map_err(|e| format!("Failed to read reference: {}", e))?;
+                    let name = reference.shorthand().unwrap_or("unknown").to_string();
+                    if let Ok(commit) = reference.peel_to_commit() {
+                        tips.push((name, commit.id()));
+                    }
+                }
+            }
+        }
+
+        // 空仓库或未诞生分支还没有任何本地分支，与 HEAD 一样按无提交处理；
+        // 已有分支时通配符或分支列表一个都没匹配上才报错
+        if tips.is_empty() && !matches!(selection, BranchSelection::AllLocal) {
+            let has_branches = self
                 .repository
-                .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
-                .map_err(|e| format!("Failed to create diff: {}", e))?;
+                .branches(Some(git2::BranchType::Local))
+                .map(|mut branches| branches.next().is_some())
+                .unwrap_or(false);
+            if has_branches {
+                return Err("No branches matched the selection".to_string());
+            }
+        }
+
+        Ok(tips)
+    }
+
+    /// 获取指定提交的 diff
+    pub fn get_commit_diff(&self, hash: &str) -> Result<String, String> {
+        let oid = git2::Oid::from_str(hash).map_err(|e| format!("Invalid commit hash: {}", e))?;
+
+        let commit = self
+            .repository
+            .find_commit(oid)
+            .map_err(|e| format!("Failed to find commit: {}", e))?;
+
+        // 生成 diff
+        let diff = self.diff_against_parent(&commit)?;
+
+        // 将 diff 转为 patch 文本
+        let mut diff_text = String::new();
+
+        diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
+            let origin = line.origin();
+            let content = std::str::from_utf8(line.content()).unwrap_or("");
+
+            match origin {
+                '+' | '-' | ' ' => {
+                    diff_text.push(origin);
+                    diff_text.push_str(content);
+                }
+                'F' => {
+                    diff_text.push_str("--- ");
+                    diff_text.push_str(content);
+                }
+                'T' => {
+                    diff_text.push_str("+++ ");
+                    diff_text.push_str(content);
+                }
+                'H' => {
+                    diff_text.push_str("@@ ");
+                    diff_text.push_str(content);
+                }
+                _ => {
+                    diff_text.push_str(content);
+                }
+            }
+
+            true
+        })
+        .map_err(|e| format!("Failed to generate diff patch: {}", e))?;
+
+        Ok(diff_text)
+    }
+
+    /// 按默认阈值（diff_summary::DEFAULT_THRESHOLD）生成提交的 diff 摘要；需要自定义阈值或路径时用 summarize_commit_diff
+    pub fn get_commit_diff_summary(&self, hash: &str) -> Result<String, String> {
+        Ok(self
+            .summarize_commit_diff(hash, &[], diff_summary::DEFAULT_THRESHOLD)?
+            .text)
+    }
+
+    /// 生成提交的结构化 diff 摘要，只计入匹配路径规则的文件（语法同路径过滤）
+    /// 完整 diff 不超过阈值时 text 为完整 diff，否则为逐文件统计、hunk 头与增删声明组成的摘要
+    pub fn summarize_commit_diff(
+        &self,
+        hash: &str,
+        patterns: &[String],
+        threshold: usize,
+    ) -> Result<DiffSummary, String> {
+        let oid = git2::Oid::from_str(hash).map_err(|e| format!("Invalid commit hash: {}", e))?;
+        let commit = self
+            .repository
+            .find_commit(oid)
+            .map_err(|e| format!("Failed to find commit: {}", e))?;
+
+        let diff = self.diff_with_renames(&commit)?;
+        diff_summary::summarize(&diff, &PathMatcher::new(patterns)?, threshold)
+    }
+
+    /// 基于提交集合统计仓库数据
+    pub fn get_stats(&self, commits: &[Commit]) -> Result<RepoStats, String> {
+        self.get_stats_filtered(commits, &CommitFilter::default())
+    }
+
+    /// 基于提交集合统计仓库数据，只计入匹配路径过滤的文件
+    pub fn get_stats_filtered(
+        &self,
+        commits: &[Commit],
+        filter: &CommitFilter,
+    ) -> Result<RepoStats, String> {
+        let path_matcher = PathMatcher::new(&filter.paths)?;
+        let mut authors = HashSet::new();
+        let mut changed = Vec::new();
 
-            // 汇总统计数据
-            let stats = diff
-                .stats()
-                .map_err(|e| format!("Failed to get diff stats: {}", e))?;
+        // 收集作者集合（按规范身份去重）
+        for commit in commits {
+            authors.extend(commit.participant_keys());
+        }
+
+        // 逐个提交汇总文件统计；采集时已带明细的提交无需再计算 diff
+        for commit in commits {
+            let computed;
+            let files = match &commit.files {
+                Some(files) => files,
+                None => {
+                    let oid = git2::Oid::from_str(&commit.hash)
+                        .map_err(|e| format!("Invalid commit hash: {}", e))?;
+                    let git_commit = self
+                        .repository
+                        .find_commit(oid)
+                        .map_err(|e| format!("Failed to find commit: {}", e))?;
+                    computed = self.file_changes(&git_commit)?;
+                    &computed
+                }
+            };
 
-            files_changed += stats.files_changed();
-            insertions += stats.insertions();
-            deletions += stats.deletions();
+            changed.extend(files.iter().filter(|f| path_matcher.matches(f)).cloned());
         }
 
+        self.save_index();
+
         Ok(RepoStats {
             total_commits: commits.len(),
             authors: authors.len(),
-            files_changed,
-            insertions,
-            deletions,
+            files_changed: changed.len(),
+            insertions: changed.iter().map(|f| f.insertions).sum(),
+            deletions: changed.iter().map(|f| f.deletions).sum(),
+            languages: self.languages.breakdown(&changed),
+            activity: Self::activity_stats(commits, &self.timezone),
+        })
+    }
+
+    /// 按当地时间统计提交的星期 / 小时分布、活跃天数与每日首末提交时间
+    pub fn activity_stats(commits: &[Commit], timezone: &TimeZone) -> ActivityStats {
+        let mut activity = ActivityStats {
+            timezone: timezone.name().to_string(),
+            by_weekday: vec![0; 7],
+            by_hour: vec![0; 24],
+            heatmap: vec![vec![0; 24]; 7],
+            ..Default::default()
+        };
+        // 日期 -> (提交数, 最早, 最晚)
+        let mut days: BTreeMap<NaiveDate, (usize, NaiveTime, NaiveTime)> = BTreeMap::new();
+
+        for commit in commits {
+            let local = timezone.local(commit.timestamp);
+            let weekday = local.weekday().num_days_from_monday() as usize;
+            let hour = local.hour();
+
+            activity.by_weekday[weekday] += 1;
+            activity.by_hour[hour as usize] += 1;
+            activity.heatmap[weekday][hour as usize] += 1;
+            if weekday >= 5 {
+                activity.weekend_commits += 1;
+            } else if !WORK_HOURS.contains(&hour) {
+                activity.off_hours_commits += 1;
+            }
+
+            let time = local.time();
+            let day = days.entry(local.date_naive()).or_insert((0, time, time));
+            day.0 += 1;
+            day.1 = day.1.min(time);
+            day.2 = day.2.max(time);
+        }
+
+        let mut streak = 0;
+        let mut previous: Option<NaiveDate> = None;
+        for date in days.keys() {
+            streak = match previous {
+                Some(previous) if previous.succ_opt() == Some(*date) => streak + 1,
+                _ => 1,
+            };
+            activity.longest_streak = activity.longest_streak.max(streak);
+            previous = Some(*date);
+        }
+
+        if let (Some(first), Some(last)) = (days.keys().next(), days.keys().next_back()) {
+            activity.span_days = (*last - *first).num_days() as usize + 1;
+        }
+        activity.active_days = days.len();
+        activity.days = days
+            .into_iter()
+            .map(|(date, (commits, first, last))| DayActivity {
+                date: date.format("%Y-%m-%d").to_string(),
+                commits,
+                first_time: first.format("%H:%M").to_string(),
+                last_time: last.format("%H:%M").to_string(),
+            })
+            .collect();
+
+        activity
+    }
+
+    /// 统计 HEAD 上存活代码的归属；未指定路径时分析时间窗口内改动过的文件
+    pub fn get_ownership(
+        &self,
+        from: i64,
+        to: i64,
+        filter: &CommitFilter,
+        options: &OwnershipOptions,
+    ) -> Result<OwnershipSummary, String> {
+        let deadline = Instant::now() + Duration::from_millis(options.time_budget_ms);
+        let commits = if options.paths.is_empty() {
+            self.get_commits_filtered(from, to, filter)?
+        } else {
+            Vec::new()
+        };
+        let files = self.ownership_candidates(&commits, options)?;
+        self.analyze_ownership(&files, deadline)
+    }
+
+    /// 代码归属待分析的文件：指定了路径时取 HEAD 上匹配的文件；
+    /// 否则取提交中改动过的文件，改动次数多的在前，预算不足时优先分析
+    pub fn ownership_candidates(
+        &self,
+        commits: &[Commit],
+        options: &OwnershipOptions,
+    ) -> Result<Vec<String>, String> {
+        if options.paths.is_empty() {
+            let mut touched: HashMap<&str, usize> = HashMap::new();
+            for file in commits.iter().flat_map(|c| c.files.iter().flatten()) {
+                if file.kind != ChangeKind::Deleted {
+                    *touched.entry(file.path.as_str()).or_default() += 1;
+                }
+            }
+
+            let mut files: Vec<(&str, usize)> = touched.into_iter().collect();
+            files.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+            return Ok(files
+                .into_iter()
+                .map(|(path, _)| path.to_string())
+                .collect());
+        }
+
+        let Some(head) = self.head_commit() else {
+            return Ok(Vec::new());
+        };
+        let tree = head
+            .tree()
+            .map_err(|e| format!("Failed to get commit tree: {}", e))?;
+        let matcher = PathMatcher::new(&options.paths)?;
+
+        let mut files = Vec::new();
+        tree.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+            if entry.kind() == Some(git2::ObjectType::Blob) {
+                let path = format!("{}{}", dir, entry.name().unwrap_or_default());
+                if matcher.matches_paths(&path, None) {
+                    files.push(path);
+                }
+            }
+            git2::TreeWalkResult::Ok
+        })
+        .map_err(|e| format!("Failed to walk tree: {}", e))?;
+
+        Ok(files)
+    }
+
+    /// 在 HEAD 上逐个文件执行 blame，按规范作者与顶层目录汇总存活行数
+    /// 单个文件的 blame 无法中途打断，到达截止时间后不再分析剩余文件
+    pub fn analyze_ownership(
+        &self,
+        files: &[String],
+        deadline: Instant,
+    ) -> Result<OwnershipSummary, String> {
+        let mut summary = OwnershipSummary::default();
+        let Some(head) = self.head_commit() else {
+            return Ok(summary);
+        };
+        let tree = head
+            .tree()
+            .map_err(|e| format!("Failed to get commit tree: {}", e))?;
+
+        // 原始签名 -> 规范身份；作者去重键 -> 规范身份
+        let mut resolved: HashMap<(String, String), (String, String)> = HashMap::new();
+        let mut identities: HashMap<String, (String, String)> = HashMap::new();
+        let mut authors: HashMap<String, usize> = HashMap::new();
+        let mut directories: HashMap<String, HashMap<String, usize>> = HashMap::new();
+
+        for (index, path) in files.iter().enumerate() {
+            if Instant::now() >= deadline {
+                summary.truncated = true;
+                summary.files_skipped += files.len() - index;
+                break;
+            }
+
+            // HEAD 上已不存在的文件直接忽略
+            let Some(entry) = tree
+                .get_path(Path::new(path))
+                .ok()
+                .filter(|e| e.kind() == Some(git2::ObjectType::Blob))
+            else {
+                continue;
+            };
+            let blame = self
+                .repository
+                .find_blob(entry.id())
+                .ok()
+                .filter(|blob| !blob.is_binary() && blob.size() <= MAX_BLAME_FILE_SIZE)
+                .and_then(|_| self.repository.blame_file(Path::new(path), None).ok());
+            let Some(blame) = blame else {
+                summary.files_skipped += 1;
+                continue;
+            };
+
+            summary.files_blamed += 1;
+            let directory = directories.entry(rollup_directory(path, 1)).or_default();
+            for hunk in blame.iter() {
+                let signature = hunk.final_signature();
+                let raw = (
+                    signature.name().unwrap_or("Unknown").to_string(),
+                    signature.email().unwrap_or("").to_string(),
+                );
+                let (name, email) = resolved
+                    .entry(raw)
+                    .or_insert_with_key(|(name, email)| self.identity.resolve(name, email))
+                    .clone();
+                let key = if email.is_empty() {
+                    name.to_lowercase()
+                } else {
+                    email.to_lowercase()
+                };
+                let lines = hunk.lines_in_hunk();
+
+                summary.total_lines += lines;
+                *authors.entry(key.clone()).or_default() += lines;
+                *directory.entry(key.clone()).or_default() += lines;
+                identities.entry(key).or_insert((name, email));
+            }
+        }
+
+        summary.authors = owner_shares(authors, &identities, usize::MAX);
+        summary.directories = directories
+            .into_iter()
+            .map(|(path, owners)| DirectoryOwnership {
+                path,
+                lines: owners.values().sum(),
+                owners: owner_shares(owners, &identities, MAX_DIRECTORY_OWNERS),
+            })
+            .filter(|directory| directory.lines > 0)
+            .collect();
+        summary
+            .directories
+            .sort_by(|a, b| b.lines.cmp(&a.lines).then(a.path.cmp(&b.path)));
+
+        Ok(summary)
+    }
+
+    /// HEAD 指向的提交（空仓库或 HEAD 无效时为 None）
+    fn head_commit(&self) -> Option<git2::Commit<'_>> {
+        self.repository.head().ok()?.peel_to_commit().ok()
+    }
+
+    /// 统计时间窗口内的改动热点（路径过滤同 get_commits_filtered）
+    pub fn get_hotspots(
+        &self,
+        from: i64,
+        to: i64,
+        filter: &CommitFilter,
+        options: &HotspotOptions,
+    ) -> Result<Hotspots, String> {
+        let commits = self.get_commits_filtered(from, to, filter)?;
+        Ok(Self::rank_hotspots(&commits, options))
+    }
+
+    /// 按提交数、变更行数与作者数排出文件和目录热点，目录按 options.depth 层汇总
+    /// 被合并提交也在列表中时跳过对应的合并提交，避免同一改动计两次
+    pub fn rank_hotspots(commits: &[Commit], options: &HotspotOptions) -> Hotspots {
+        let listed: HashSet<&str> = commits.iter().map(|c| c.hash.as_str()).collect();
+        let mut files: HashMap<String, Churn> = HashMap::new();
+        let mut directories: HashMap<String, Churn> = HashMap::new();
+
+        for commit in commits {
+            if commit
+                .parents
+                .iter()
+                .skip(1)
+                .any(|p| listed.contains(p.as_str()))
+            {
+                continue;
+            }
+
+            let authors = commit.participant_keys();
+            for file in commit.files.iter().flatten() {
+                files
+                    .entry(file.path.clone())
+                    .or_default()
+                    .add(commit, file, &authors);
+                directories
+                    .entry(rollup_directory(&file.path, options.depth))
+                    .or_default()
+                    .add(commit, file, &authors);
+            }
+        }
+
+        Hotspots {
+            files: rank_churn(files, options.limit),
+            directories: rank_churn(directories, options.limit),
+        }
+    }
+}
+
+/// 单个文件或目录在窗口内的累计改动
+#[derive(Default)]
+struct Churn {
+    commits: HashSet<String>,
+    insertions: usize,
+    deletions: usize,
+    authors: HashSet<String>,
+}
+
+impl Churn {
+    fn add(&mut self, commit: &Commit, file: &FileChange, authors: &[String]) {
+        self.commits.insert(commit.hash.clone());
+        self.insertions += file.insertions;
+        self.deletions += file.deletions;
+        self.authors.extend(authors.iter().cloned());
+    }
+}
+
+/// 按行数从多到少列出作者及占比，最多 limit 位
+fn owner_shares(
+    lines: HashMap<String, usize>,
+    identities: &HashMap<String, (String, String)>,
+    limit: usize,
+) -> Vec<OwnerShare> {
+    let total: usize = lines.values().sum();
+    let mut shares: Vec<OwnerShare> = lines
+        .into_iter()
+        .map(|(key, lines)| {
+            let (name, email) = identities.get(&key).cloned().unwrap_or_default();
+            OwnerShare {
+                name,
+                email,
+                lines,
+                percent: if total > 0 {
+                    (lines as f64 * 1000.0 / total as f64).round() / 10.0
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect();
+
+    shares.sort_by(|a, b| b.lines.cmp(&a.lines).then(a.name.cmp(&b.name)));
+    shares.truncate(limit);
+    shares
+}
+
+/// 排序并截取前 limit 项（0 表示不限）
+fn rank_churn(churn: HashMap<String, Churn>, limit: usize) -> Vec<Hotspot> {
+    let mut ranked: Vec<Hotspot> = churn
+        .into_iter()
+        .map(|(path, churn)| Hotspot {
+            path,
+            commits: churn.commits.len(),
+            insertions: churn.insertions,
+            deletions: churn.deletions,
+            authors: churn.authors.len(),
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| {
+        b.commits
+            .cmp(&a.commits)
+            .then(b.lines_changed().cmp(&a.lines_changed()))
+            .then(b.authors.cmp(&a.authors))
+            .then(a.path.cmp(&b.path))
+    });
+    if limit > 0 {
+        ranked.truncate(limit);
+    }
+    ranked
+}
+
+/// 文件所在目录截取到前 depth 层；仓库根目录下的文件记为 `.`
+fn rollup_directory(path: &str, depth: usize) -> String {
+    let mut dirs: Vec<&str> = path.split('/').collect();
+    dirs.pop();
+    dirs.truncate(depth.max(1));
+    if dirs.is_empty() {
+        ".".to_string()
+    } else {
+        dirs.join("/")
+    }
+}
+
+/// 目录是否为仓库根：含 `.git`（目录或工作树 / 子模块的 gitfile），或本身是裸仓库
+fn is_repository_dir(dir: &Path) -> bool {
+    dir.join(".git").exists()
+        || (dir.join("HEAD").is_file() && dir.join("objects").is_dir() && dir.join("refs").is_dir())
+}
+
+/// 文件变更类型、当前路径与重命名 / 复制前的路径
+pub(crate) fn delta_paths(delta: &git2::DiffDelta) -> (ChangeKind, String, Option<String>) {
+    let kind = match delta.status() {
+        git2::Delta::Added => ChangeKind::Added,
+        git2::Delta::Deleted => ChangeKind::Deleted,
+        git2::Delta::Renamed => ChangeKind::Renamed,
+        git2::Delta::Copied => ChangeKind::Copied,
+        git2::Delta::Typechange => ChangeKind::TypeChange,
+        _ => ChangeKind::Modified,
+    };
+
+    let new_path = delta
+        .new_file()
+        .path()
+        .map(|p| p.to_string_lossy().to_string());
+    let old_path = delta
+        .old_file()
+        .path()
+        .map(|p| p.to_string_lossy().to_string());
+    let path = match kind {
+        ChangeKind::Deleted => old_path.clone(),
+        _ => new_path.or_else(|| old_path.clone()),
+    }
+    .unwrap_or_default();
+
+    let old_path = match kind {
+        ChangeKind::Renamed | ChangeKind::Copied => old_path,
+        _ => None,
+    };
+
+    (kind, path, old_path)
+}
+
+/// 树中指定路径上的子模块指针（gitlink）
+fn gitlink_at(tree: &git2::Tree, path: &str) -> Option<git2::Oid> {
+    tree.get_path(Path::new(path))
+        .ok()
+        .filter(|entry| entry.kind() == Some(git2::ObjectType::Commit))
+        .map(|entry| entry.id())
+}
+
+/// 解析语义化版本标签，允许 `v` 前缀
+fn parse_semver_tag(name: &str) -> Option<semver::Version> {
+    let version = name
+        .strip_prefix('v')
+        .or_else(|| name.strip_prefix('V'))
+        .unwrap_or(name);
+    semver::Version::parse(version).ok()
+}
+
+/// 按所选时间口径读取提交时间
+fn commit_time_by(commit: &git2::Commit, time_key: TimeKey) -> i64 {
+    match time_key {
+        TimeKey::Committer => commit.time().seconds(),
+        TimeKey::Author => commit.author().when().seconds(),
+    }
+}
+
+/// 作者身份解析：先应用仓库 .mailmap，再应用应用级别名表
+struct IdentityResolver {
+    mailmap: Option<git2::Mailmap>,
+    aliases: Vec<AuthorAlias>,
+}
+
+impl IdentityResolver {
+    /// 解析提交作者的规范姓名与邮箱
+    fn resolve_author(&self, commit: &git2::Commit) -> (String, String) {
+        let author = match &self.mailmap {
+            Some(mailmap) => commit
+                .author_with_mailmap(mailmap)
+                .unwrap_or_else(|_| commit.author().to_owned()),
+            None => commit.author().to_owned(),
+        };
+
+        self.apply_aliases(
+            author.name().unwrap_or("Unknown"),
+            author.email().unwrap_or(""),
+        )
+    }
+
+    /// 解析任意姓名 / 邮箱的规范身份（用于 Co-authored-by 等脚注）
+    fn resolve(&self, name: &str, email: &str) -> (String, String) {
+        let signature = self.mailmap.as_ref().and_then(|mailmap| {
+            let raw = git2::Signature::new(name, email, &git2::Time::new(0, 0)).ok()?;
+            mailmap.resolve_signature(&raw).ok()
+        });
+
+        match signature {
+            Some(sig) => {
+                self.apply_aliases(sig.name().unwrap_or(name), sig.email().unwrap_or(email))
+            }
+            None => self.apply_aliases(name, email),
+        }
+    }
+
+    /// 解析提交的合作者：去重，并排除作者本人
+    fn resolve_co_authors(&self, commit: &git2::Commit, author_email: &str) -> Vec<CoAuthor> {
+        let mut seen = HashSet::new();
+        seen.insert(author_email.to_lowercase());
+
+        commit_parser::parse_co_authors(commit.message().unwrap_or(""))
+            .into_iter()
+            .map(|(name, email)| {
+                let (name, email) = self.resolve(&name, &email);
+                CoAuthor { name, email }
+            })
+            .filter(|co| seen.insert(co.key()))
+            .collect()
+    }
+
+    fn apply_aliases(&self, name: &str, email: &str) -> (String, String) {
+        let Some(alias) = self.aliases.iter().find(|alias| alias.matches(name, email)) else {
+            return (name.to_string(), email.to_string());
+        };
+
+        // 别名表未填写的字段保留原值
+        let name = if alias.name.is_empty() {
+            name
+        } else {
+            &alias.name
+        };
+        let email = if alias.email.is_empty() {
+            email
+        } else {
+            &alias.email
+        };
+        (name.to_string(), email.to_string())
+    }
+}
+
+/// 作者匹配器：预先编译通配模式，避免逐提交重复解析
+struct AuthorMatcher {
+    patterns: Vec<String>,
+    globs: Vec<glob::Pattern>,
+    mode: AuthorMatchMode,
+}
+
+impl AuthorMatcher {
+    fn new(patterns: &[String], mode: AuthorMatchMode) -> Result<Self, String> {
+        let patterns: Vec<String> = patterns
+            .iter()
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect();
+
+        let globs = if mode == AuthorMatchMode::Glob {
+            patterns
+                .iter()
+                .map(|p| {
+                    glob::Pattern::new(p)
+                        .map_err(|e| format!("Invalid author pattern '{}': {}", p, e))
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            patterns,
+            globs,
+            mode,
         })
     }
+
+    /// 作者或任一 Co-authored-by 合作者命中即视为匹配
+    fn matches_commit(&self, commit: &Commit) -> bool {
+        self.matches(&commit.author, &commit.email)
+            || commit
+                .co_authors
+                .iter()
+                .any(|co| self.matches(&co.name, &co.email))
+    }
+
+    /// 姓名或邮箱任一命中即视为匹配；未配置任何模式时全部放行
+    fn matches(&self, name: &str, email: &str) -> bool {
+        if self.patterns.is_empty() {
+            return true;
+        }
+
+        match self.mode {
+            AuthorMatchMode::Exact => self.patterns.iter().any(|p| p == name || p == email),
+            AuthorMatchMode::IgnoreCase => {
+                let name = name.to_lowercase();
+                let email = email.to_lowercase();
+                self.patterns.iter().any(|p| {
+                    let p = p.to_lowercase();
+                    p == name || p == email
+                })
+            }
+            AuthorMatchMode::Glob => {
+                let options = glob::MatchOptions {
+                    case_sensitive: false,
+                    require_literal_separator: false,
+                    require_literal_leading_dot: false,
+                };
+                self.globs
+                    .iter()
+                    .any(|g| g.matches_with(name, options) || g.matches_with(email, options))
+            }
+        }
+    }
+}
+
+/// 路径匹配器：支持包含与 `!` 前缀的排除通配
+pub(crate) struct PathMatcher {
+    includes: Vec<glob::Pattern>,
+    excludes: Vec<glob::Pattern>,
+}
+
+impl PathMatcher {
+    const OPTIONS: glob::MatchOptions = glob::MatchOptions {
+        case_sensitive: true,
+        require_literal_separator: true,
+        require_literal_leading_dot: false,
+    };
+
+    pub(crate) fn new(patterns: &[String]) -> Result<Self, String> {
+        let mut includes = Vec::new();
+        let mut excludes = Vec::new();
+
+        for raw in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            let (target, pattern) = match raw.strip_prefix('!') {
+                Some(rest) => (&mut excludes, rest),
+                None => (&mut includes, raw),
+            };
+            let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+
+            // 不含通配符的路径视为文件本身或目录前缀
+            let expanded = if pattern.contains(['*', '?', '[']) {
+                vec![pattern.to_string()]
+            } else {
+                let escaped = glob::Pattern::escape(pattern);
+                vec![escaped.clone(), format!("{}/**", escaped)]
+            };
+
+            for pattern in expanded {
+                let compiled = glob::Pattern::new(&pattern)
+                    .map_err(|e| format!("Invalid path pattern '{}': {}", raw, e))?;
+                target.push(compiled);
+            }
+        }
+
+        Ok(Self { includes, excludes })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.includes.is_empty() && self.excludes.is_empty()
+    }
+
+    /// 文件的新路径或旧路径命中包含规则、且均未命中排除规则
+    pub(crate) fn matches(&self, file: &FileChange) -> bool {
+        self.matches_paths(&file.path, file.old_path.as_deref())
+    }
+
+    pub(crate) fn matches_paths(&self, path: &str, old_path: Option<&str>) -> bool {
+        let paths = std::iter::once(path).chain(old_path);
+        let hit = |patterns: &[glob::Pattern], path: &str| {
+            patterns.iter().any(|p| p.matches_with(path, Self::OPTIONS))
+        };
+
+        let mut included = self.includes.is_empty();
+        for path in paths {
+            if hit(&self.excludes, path) {
+                return false;
+            }
+            included = included || hit(&self.includes, path);
+        }
+        included
+    }
+
+    /// 只保留匹配的文件明细；返回提交是否仍触及匹配文件
+    fn retain_files(&self, commit: &mut Commit) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+
+        match commit.files.as_mut() {
+            Some(files) => {
+                files.retain(|f| self.matches(f));
+                !files.is_empty()
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{Oid, Signature, Time};
+
+    const DAY: i64 = 24 * 60 * 60;
+    const FROM: i64 = 1_704_067_200; // 2024-01-01 00:00:00 UTC
+    const TO: i64 = FROM + 7 * DAY - 1;
+
+    const ALICE: (&str, &str) = ("Alice", "alice@example.com");
+    const BOB: (&str, &str) = ("Bob", "bob@example.com");
+
+    /// 在 HEAD 上追加一个提交，作者时间与提交时间可分别指定（提交者与作者相同）
+    /// files 为 (路径, 新内容)，内容为 None 表示删除；未列出的文件沿用父提交
+    fn commit_at(
+        repo: &Repository,
+        (name, email): (&str, &str),
+        message: &str,
+        author_time: i64,
+        commit_time: i64,
+        files: &[(&str, Option<&str>)],
+    ) -> Oid {
+        let author = Signature::new(name, email, &Time::new(author_time, 0)).unwrap();
+        let committer = Signature::new(name, email, &Time::new(commit_time, 0)).unwrap();
+        let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+        let base = match &parent {
+            Some(parent) => parent.tree().unwrap(),
+            None => repo
+                .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+                .unwrap(),
+        };
+
+        let mut update = git2::build::TreeUpdateBuilder::new();
+        for (path, content) in files {
+            match content {
+                Some(content) => {
+                    let blob = repo.blob(content.as_bytes()).unwrap();
+                    update.upsert(*path, blob, git2::FileMode::Blob)
+                }
+                None => update.remove(*path),
+            };
+        }
+        let tree = repo
+            .find_tree(update.create_updated(repo, &base).unwrap())
+            .unwrap();
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+
+        repo.commit(Some("HEAD"), &author, &committer, message, &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_author_filter_supports_exact_ignore_case_and_glob() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit_at(&repo, ALICE, "alice", FROM + DAY, FROM + DAY, &[]);
+        commit_at(&repo, BOB, "bob", FROM + 2 * DAY, FROM + 2 * DAY, &[]);
+        let ci = ("ci-bot", "ci@builds.internal");
+        commit_at(&repo, ci, "release", FROM + 3 * DAY, FROM + 3 * DAY, &[]);
+
+        let service = GitService::open_repo(dir.path().to_str().unwrap()).unwrap();
+        let authors = |patterns: &[&str], author_match: AuthorMatchMode| {
+            let filter = CommitFilter {
+                authors: patterns.iter().map(|p| p.to_string()).collect(),
+                author_match,
+                ..Default::default()
+            };
+            let mut authors: Vec<String> = service
+                .get_commits_filtered(FROM, TO, &filter)
+                .unwrap()
+                .into_iter()
+                .map(|c| c.author)
+                .collect();
+            authors.sort();
+            authors
+        };
+
+        // 姓名或邮箱任一命中即可，多个模式取并集
+        assert_eq!(
+            authors(&["alice@example.com", "Bob"], AuthorMatchMode::Exact),
+            vec!["Alice", "Bob"]
+        );
+        assert!(authors(&["ALICE"], AuthorMatchMode::Exact).is_empty());
+        assert_eq!(
+            authors(&["ALICE"], AuthorMatchMode::IgnoreCase),
+            vec!["Alice"]
+        );
+        assert_eq!(
+            authors(&["*@EXAMPLE.com"], AuthorMatchMode::Glob),
+            vec!["Alice", "Bob"]
+        );
+        assert_eq!(authors(&["ci-*"], AuthorMatchMode::Glob), vec!["ci-bot"]);
+        assert_eq!(authors(&[], AuthorMatchMode::Exact).len(), 3);
+    }
+
+    #[test]
+    fn test_mailmap_and_aliases_resolve_canonical_authors() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        fs::write(
+            dir.path().join(".mailmap"),
+            "Alice <alice@example.com> <alice@old-employer.com>\n",
+        )
+        .unwrap();
+        commit_at(&repo, ALICE, "laptop", FROM + DAY, FROM + DAY, &[]);
+        let old = ("alice", "alice@old-employer.com");
+        commit_at(
+            &repo,
+            old,
+            "old address",
+            FROM + 2 * DAY,
+            FROM + 2 * DAY,
+            &[],
+        );
+        commit_at(&repo, BOB, "bob", FROM + 3 * DAY, FROM + 3 * DAY, &[]);
+        let ci = ("ci-bot", "BOB+CI@example.com");
+        commit_at(&repo, ci, "ci", FROM + 4 * DAY, FROM + 4 * DAY, &[]);
+
+        // .mailmap 先生效，应用级别名表再按忽略大小写归并
+        let service = GitService::open_repo(dir.path().to_str().unwrap())
+            .unwrap()
+            .with_author_aliases(vec![AuthorAlias {
+                name: "Bob".to_string(),
+                email: "bob@example.com".to_string(),
+                aliases: vec!["bob+ci@example.com".to_string()],
+            }]);
+        let commits = service.get_commits(FROM, TO).unwrap();
+        let identities: Vec<(&str, &str)> = commits
+            .iter()
+            .map(|c| (c.author.as_str(), c.email.as_str()))
+            .collect();
+        assert_eq!(identities, vec![BOB, BOB, ALICE, ALICE]);
+        assert_eq!(service.get_stats(&commits).unwrap().authors, 2);
+
+        // 作者过滤同样使用规范身份
+        let filter = CommitFilter {
+            authors: vec!["alice@example.com".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            service
+                .get_commits_filtered(FROM, TO, &filter)
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_out_of_order_timestamps_are_not_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+
+        // 中间提交的时钟偏差到了窗口之前，旧实现会在这里提前终止
+        let first = commit_at(&repo, ALICE, "first", FROM + DAY, FROM + DAY, &[]);
+        commit_at(
+            &repo,
+            ALICE,
+            "skewed",
+            FROM - 30 * DAY,
+            FROM - 30 * DAY,
+            &[],
+        );
+        let last = commit_at(&repo, ALICE, "last", FROM + 2 * DAY, FROM + 2 * DAY, &[]);
+
+        let service = GitService::open_repo(dir.path().to_str().unwrap()).unwrap();
+        let hashes: Vec<String> = service
+            .get_commits(FROM, TO)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.hash)
+            .collect();
+
+        assert_eq!(hashes, vec![last.to_string(), first.to_string()]);
+    }
+
+    #[test]
+    fn test_time_key_selects_author_or_committer_date() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+
+        // 上个月编写、本周变基落地
+        let rebased = commit_at(&repo, ALICE, "rebased", FROM - 20 * DAY, FROM + DAY, &[]);
+
+        let service = GitService::open_repo(dir.path().to_str().unwrap()).unwrap();
+        let by_committer = service.get_commits(FROM, TO).unwrap();
+        assert_eq!(by_committer.len(), 1);
+        assert_eq!(by_committer[0].hash, rebased.to_string());
+
+        let filter = CommitFilter {
+            time_key: TimeKey::Author,
+            ..Default::default()
+        };
+        let by_author = service.get_commits_filtered(FROM, TO, &filter).unwrap();
+        assert!(by_author.is_empty());
+    }
+
+    #[test]
+    fn test_merge_policies_select_and_fold_merged_commits() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+
+        let base = commit_at(&repo, ALICE, "base", FROM + DAY, FROM + DAY, &[]);
+        let feature = commit_at(&repo, BOB, "feature", FROM + 2 * DAY, FROM + 2 * DAY, &[]);
+        // 主线回到 base 上继续提交，再把 feature 合并进来
+        let branch = repo.head().unwrap().name().unwrap().to_string();
+        repo.reference(&branch, base, true, "rewind").unwrap();
+        let main = commit_at(&repo, ALICE, "main", FROM + 3 * DAY, FROM + 3 * DAY, &[]);
+        let main = repo.find_commit(main).unwrap();
+        let signature = Signature::new(ALICE.0, ALICE.1, &Time::new(FROM + 4 * DAY, 0)).unwrap();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "Merge feature",
+            &main.tree().unwrap(),
+            &[&main, &repo.find_commit(feature).unwrap()],
+        )
+        .unwrap();
+
+        let service = GitService::open_repo(dir.path().to_str().unwrap()).unwrap();
+        let collect = |merge_policy: MergePolicy| {
+            let filter = CommitFilter {
+                merge_policy,
+                ..Default::default()
+            };
+            service.get_commits_filtered(FROM, TO, &filter).unwrap()
+        };
+        let messages = |commits: &[Commit]| {
+            let mut messages: Vec<String> = commits.iter().map(|c| c.message.clone()).collect();
+            messages.sort();
+            messages
+        };
+
+        assert_eq!(
+            messages(&collect(MergePolicy::All)),
+            vec!["Merge feature", "base", "feature", "main"]
+        );
+        assert_eq!(
+            messages(&collect(MergePolicy::ExcludeMerges)),
+            vec!["base", "feature", "main"]
+        );
+        assert_eq!(
+            messages(&collect(MergePolicy::FirstParent)),
+            vec!["Merge feature", "base", "main"]
+        );
+
+        // 只保留合并提交，被合并分支上的提交折叠到它下面
+        let merges = collect(MergePolicy::MergesOnly);
+        assert_eq!(messages(&merges), vec!["Merge feature"]);
+        assert_eq!(messages(&merges[0].merged_commits), vec!["feature"]);
+    }
+
+    #[test]
+    fn test_file_changes_detect_renames_and_binary_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let original: String = (1..=10)
+            .map(|i| format!("fn step_{}() {{}}\n", i))
+            .collect();
+        commit_at(
+            &repo,
+            ALICE,
+            "add",
+            FROM + DAY,
+            FROM + DAY,
+            &[
+                ("src/old_name.rs", Some(original.as_str())),
+                ("logo.png", Some("\u{0}PNG")),
+            ],
+        );
+        let edited = original.replace("step_10", "step_ten");
+        commit_at(
+            &repo,
+            ALICE,
+            "rename",
+            FROM + 2 * DAY,
+            FROM + 2 * DAY,
+            &[
+                ("src/old_name.rs", None),
+                ("src/new_name.rs", Some(edited.as_str())),
+            ],
+        );
+
+        let service = GitService::open_repo(dir.path().to_str().unwrap()).unwrap();
+        let commits = service.get_commits(FROM, TO).unwrap();
+        let files = |commit: &Commit| {
+            let mut files: Vec<(String, Option<String>, ChangeKind, usize, usize, bool)> = commit
+                .files
+                .iter()
+                .flatten()
+                .map(|f| {
+                    (
+                        f.path.clone(),
+                        f.old_path.clone(),
+                        f.kind,
+                        f.insertions,
+                        f.deletions,
+                        f.binary,
+                    )
+                })
+                .collect();
+            files.sort_by(|a, b| a.0.cmp(&b.0));
+            files
+        };
+
+        // 改名并小幅修改仍识别为重命名，只统计实际改动的行
+        assert_eq!(
+            files(&commits[0]),
+            vec![(
+                "src/new_name.rs".to_string(),
+                Some("src/old_name.rs".to_string()),
+                ChangeKind::Renamed,
+                1,
+                1,
+                false
+            )]
+        );
+        assert_eq!(
+            files(&commits[1]),
+            vec![
+                ("logo.png".to_string(), None, ChangeKind::Added, 0, 0, true),
+                (
+                    "src/old_name.rs".to_string(),
+                    None,
+                    ChangeKind::Added,
+                    10,
+                    0,
+                    false
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_path_filters_follow_renames_and_limit_stats() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let tax = "pub const RATE: f64 = 0.2;\n";
+        commit_at(
+            &repo,
+            ALICE,
+            "seed",
+            FROM + DAY,
+            FROM + DAY,
+            &[
+                ("services/billing/invoice.rs", Some("a\nb\nc\n")),
+                ("services/billing/legacy.rs", Some("old\n")),
+                ("services/billing/Cargo.lock", Some("lock\n")),
+                ("services/auth/login.rs", Some("x\ny\n")),
+                ("services/shared/tax.rs", Some(tax)),
+            ],
+        );
+        let other_team = [("services/auth/login.rs", Some("x\n"))];
+        commit_at(
+            &repo,
+            BOB,
+            "auth",
+            FROM + 2 * DAY,
+            FROM + 2 * DAY,
+            &other_team,
+        );
+        let lock_only = [("services/billing/Cargo.lock", Some("lock 2\n"))];
+        commit_at(
+            &repo,
+            BOB,
+            "lock",
+            FROM + 3 * DAY,
+            FROM + 3 * DAY,
+            &lock_only,
+        );
+        // 移入与移出目录的重命名，新旧路径任一命中都算触及
+        let moved_in = [
+            ("services/shared/tax.rs", None),
+            ("services/billing/tax.rs", Some(tax)),
+        ];
+        commit_at(
+            &repo,
+            ALICE,
+            "move in",
+            FROM + 4 * DAY,
+            FROM + 4 * DAY,
+            &moved_in,
+        );
+        let moved_out = [
+            ("services/billing/legacy.rs", None),
+            ("archive/legacy.rs", Some("old\n")),
+        ];
+        commit_at(
+            &repo,
+            ALICE,
+            "move out",
+            FROM + 5 * DAY,
+            FROM + 5 * DAY,
+            &moved_out,
+        );
+
+        let service = GitService::open_repo(dir.path().to_str().unwrap()).unwrap();
+        let filter = CommitFilter {
+            paths: vec!["services/billing/**".to_string(), "!**/*.lock".to_string()],
+            ..Default::default()
+        };
+        let commits = service.get_commits_filtered(FROM, TO, &filter).unwrap();
+        let touched: Vec<(&str, Vec<&str>)> = commits
+            .iter()
+            .map(|c| {
+                let mut paths: Vec<&str> =
+                    c.files.iter().flatten().map(|f| f.path.as_str()).collect();
+                paths.sort();
+                (c.message.as_str(), paths)
+            })
+            .collect();
+        assert_eq!(
+            touched,
+            vec![
+                ("move out", vec!["archive/legacy.rs"]),
+                ("move in", vec!["services/billing/tax.rs"]),
+                (
+                    "seed",
+                    vec!["services/billing/invoice.rs", "services/billing/legacy.rs"]
+                ),
+            ]
+        );
+
+        // 统计只计入匹配的文件
+        let stats = service.get_stats_filtered(&commits, &filter).unwrap();
+        assert_eq!(stats.total_commits, 3);
+        assert_eq!(stats.insertions, 4);
+        assert_eq!(stats.deletions, 0);
+    }
+
+    #[test]
+    fn test_scan_skips_inactive_and_reports_unreadable_repositories() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_str().unwrap();
+
+        let active = Repository::init(dir.path().join("active")).unwrap();
+        commit_at(&active, ALICE, "current", FROM + DAY, FROM + DAY, &[]);
+        let inactive = Repository::init(dir.path().join("inactive")).unwrap();
+        commit_at(
+            &inactive,
+            ALICE,
+            "old",
+            FROM - 30 * DAY,
+            FROM - 30 * DAY,
+            &[],
+        );
+        // 本周之前编写、窗口结束后才合入：只有按作者时间才算活跃
+        let rebased = Repository::init(dir.path().join("rebased")).unwrap();
+        commit_at(&rebased, ALICE, "late", FROM + DAY, TO + 10 * DAY, &[]);
+        // 分支指向不存在的对象
+        let corrupt = Repository::init(dir.path().join("corrupt")).unwrap();
+        commit_at(&corrupt, ALICE, "init", FROM + DAY, FROM + DAY, &[]);
+        let branch = corrupt.head().unwrap().name().unwrap().to_string();
+        fs::write(
+            corrupt.path().join(&branch),
+            format!("{}\n", "1".repeat(40)),
+        )
+        .unwrap();
+
+        let names = |scanned: &ScanResult| -> Vec<String> {
+            scanned.repos.iter().map(|r| r.name.clone()).collect()
+        };
+
+        let scanned =
+            GitService::discover_repositories(root, FROM, TO, &ScanOptions::default()).unwrap();
+        assert_eq!(names(&scanned), vec!["active"]);
+        assert_eq!(scanned.errors.len(), 1);
+        assert!(scanned.errors[0].repo_path.ends_with("corrupt"));
+
+        let options = ScanOptions {
+            time_key: TimeKey::Author,
+            ..Default::default()
+        };
+        let scanned = GitService::discover_repositories(root, FROM, TO, &options).unwrap();
+        assert_eq!(names(&scanned), vec!["active", "rebased"]);
+    }
+
+    #[test]
+    fn test_scan_walks_selected_branches_and_full_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_str().unwrap();
+
+        // 本周只在功能分支上有提交，HEAD 停在旧提交
+        let feature = Repository::init(dir.path().join("feature")).unwrap();
+        let base = commit_at(
+            &feature,
+            ALICE,
+            "old",
+            FROM - 30 * DAY,
+            FROM - 30 * DAY,
+            &[],
+        );
+        commit_at(&feature, ALICE, "current", FROM + DAY, FROM + DAY, &[]);
+        let current = feature.head().unwrap().peel_to_commit().unwrap();
+        feature.branch("feature/billing", &current, false).unwrap();
+        let head = feature.head().unwrap().name().unwrap().to_string();
+        feature
+            .reference(&head, base, true, "rewind to the old commit")
+            .unwrap();
+        // 时钟偏差：窗口内的提交之后还有一个提交时间更早的子提交
+        let skewed = Repository::init(dir.path().join("skewed")).unwrap();
+        commit_at(&skewed, ALICE, "current", FROM + DAY, FROM + DAY, &[]);
+        commit_at(
+            &skewed,
+            ALICE,
+            "skewed",
+            FROM - 30 * DAY,
+            FROM - 30 * DAY,
+            &[],
+        );
+
+        let names = |options: &ScanOptions| -> Vec<String> {
+            GitService::discover_repositories(root, FROM, TO, options)
+                .unwrap()
+                .repos
+                .into_iter()
+                .map(|r| r.name)
+                .collect()
+        };
+
+        assert_eq!(names(&ScanOptions::default()), vec!["skewed"]);
+        let all_branches = ScanOptions {
+            branches: BranchSelection::AllLocal,
+            ..Default::default()
+        };
+        assert_eq!(names(&all_branches), vec!["feature", "skewed"]);
+        // 没有匹配分支的仓库不算活跃
+        let feature_glob = ScanOptions {
+            branches: BranchSelection::Glob {
+                pattern: "feature/*".to_string(),
+            },
+            ..Default::default()
+        };
+        assert_eq!(names(&feature_glob), vec!["feature"]);
+    }
+
+    #[test]
+    fn test_releases_record_previous_semver_tag() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let tagger = Signature::new("Bob", "bob@example.com", &Time::new(FROM + DAY, 0)).unwrap();
+
+        let old = commit_at(
+            &repo,
+            ALICE,
+            "old release",
+            FROM - 10 * DAY,
+            FROM - 10 * DAY,
+            &[],
+        );
+        repo.tag_lightweight("v1.0.0", &repo.find_object(old, None).unwrap(), false)
+            .unwrap();
+        let new = commit_at(&repo, ALICE, "new release", FROM + DAY, FROM + DAY, &[]);
+        repo.tag(
+            "v1.1.0",
+            &repo.find_object(new, None).unwrap(),
+            &tagger,
+            "Billing v2\n",
+            false,
+        )
+        .unwrap();
+
+        let service = GitService::open_repo(dir.path().to_str().unwrap()).unwrap();
+        let releases = service.get_releases(FROM, TO).unwrap();
+
+        assert_eq!(releases.len(), 1);
+        assert_eq!(releases[0].name, "v1.1.0");
+        assert!(releases[0].annotated);
+        assert_eq!(releases[0].tagger.as_deref(), Some("Bob"));
+        assert_eq!(releases[0].message.as_deref(), Some("Billing v2"));
+        assert_eq!(releases[0].previous_tag.as_deref(), Some("v1.0.0"));
+    }
+
+    #[test]
+    fn test_open_from_subdirectory_and_bare_mirror() {
+        let dir = tempfile::tempdir().unwrap();
+        let work = dir.path().join("billing");
+        let repo = Repository::init(&work).unwrap();
+        commit_at(&repo, ALICE, "init", FROM, FROM, &[]);
+        fs::create_dir_all(work.join("src/api")).unwrap();
+
+        let service = GitService::open_repo(work.join("src/api").to_str().unwrap()).unwrap();
+        let info = service.get_repo_info().unwrap();
+        assert_eq!(info.name, "billing");
+        assert_eq!(Path::new(&info.path), work.as_path());
+
+        let mirror = dir.path().join("mirror.git");
+        let bare = Repository::init_bare(&mirror).unwrap();
+        bare.remote("origin", "git@example.com:org/payments.git")
+            .unwrap();
+        commit_at(&bare, ALICE, "init", FROM, FROM, &[]);
+
+        let service = GitService::open_repo(mirror.to_str().unwrap()).unwrap();
+        assert_eq!(service.get_repo_info().unwrap().name, "payments");
+    }
+
+    #[test]
+    fn test_empty_and_detached_repositories_do_not_fail() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        let service = GitService::open_repo(path).unwrap();
+        let info = service.get_repo_info().unwrap();
+        assert!(matches!(info.state, RepoState::Empty { .. }));
+        assert_eq!(info.total_commits, 0);
+        assert!(service.get_commits(FROM, TO).unwrap().is_empty());
+        assert_eq!(
+            service.collection_warnings(FROM).unwrap(),
+            vec![RepoWarning::Empty]
+        );
+
+        let first = commit_at(&repo, ALICE, "first", FROM + DAY, FROM + DAY, &[]);
+        repo.set_head_detached(first).unwrap();
+
+        let service = GitService::open_repo(path).unwrap();
+        let info = service.get_repo_info().unwrap();
+        let short = first.to_string()[..7].to_string();
+        assert_eq!(info.branch, format!("HEAD detached at {}", short));
+        assert_eq!(service.get_commits(FROM, TO).unwrap().len(), 1);
+        assert_eq!(
+            service.collection_warnings(FROM).unwrap(),
+            vec![RepoWarning::DetachedHead { commit: short }]
+        );
+    }
+
+    #[test]
+    fn test_branch_selection_on_unborn_repository_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let path = dir.path().to_str().unwrap();
+        let filter = |branches: BranchSelection| CommitFilter {
+            branches,
+            ..Default::default()
+        };
+        let feature = || BranchSelection::Glob {
+            pattern: "feature/*".to_string(),
+        };
+
+        let service = GitService::open_repo(path).unwrap();
+        for selection in [BranchSelection::Head, BranchSelection::AllLocal, feature()] {
+            let commits = service
+                .get_commits_filtered(FROM, TO, &filter(selection))
+                .unwrap();
+            assert!(commits.is_empty());
+        }
+
+        // 有分支后，没有匹配的通配符仍然报错
+        commit_at(&repo, ALICE, "first", FROM + DAY, FROM + DAY, &[]);
+        let service = GitService::open_repo(path).unwrap();
+        assert_eq!(
+            service
+                .get_commits_filtered(FROM, TO, &filter(BranchSelection::AllLocal))
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            service
+                .get_commits_filtered(FROM, TO, &filter(feature()))
+                .unwrap_err(),
+            "No branches matched the selection"
+        );
+    }
+
+    #[test]
+    fn test_commit_index_follows_new_and_rewritten_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let path = dir.path().to_str().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        let index_file = commit_index::index_path(cache.path(), repo.path());
+        let messages = || -> Vec<String> {
+            GitService::open_repo(path)
+                .unwrap()
+                .with_index_dir(Some(cache.path().to_path_buf()))
+                .get_commits(FROM, TO)
+                .unwrap()
+                .into_iter()
+                .map(|c| c.message)
+                .collect()
+        };
+
+        let first = commit_at(&repo, ALICE, "first", FROM + DAY, FROM + DAY, &[]);
+        let second = commit_at(&repo, ALICE, "second", FROM + 2 * DAY, FROM + 2 * DAY, &[]);
+        assert_eq!(messages(), vec!["second", "first"]);
+        assert!(index_file.exists());
+        // 仓库目录内不写入任何文件
+        assert!(!repo.path().join("gitlog-report").exists());
+
+        // 在已索引起点之上继续提交：增量补入，只在索引文件末尾追加
+        let before = fs::read_to_string(&index_file).unwrap();
+        commit_at(&repo, ALICE, "third", FROM + 3 * DAY, FROM + 3 * DAY, &[]);
+        assert_eq!(messages(), vec!["third", "second", "first"]);
+        let after = fs::read_to_string(&index_file).unwrap();
+        assert!(after.len() > before.len() && after.starts_with(&before));
+
+        // 强推：分支回退到 first 后另起提交，旧提交从索引中清除
+        let branch = repo.head().unwrap().name().unwrap().to_string();
+        repo.reference(&branch, first, true, "rewrite").unwrap();
+        commit_at(
+            &repo,
+            ALICE,
+            "rewritten",
+            FROM + 4 * DAY,
+            FROM + 4 * DAY,
+            &[],
+        );
+        assert_eq!(messages(), vec!["rewritten", "first"]);
+
+        let stored = fs::read_to_string(&index_file).unwrap();
+        assert!(!stored.contains(&second.to_string()));
+
+        // 索引目录不可写时照常返回结果，并在采集告警中说明
+        let blocked = cache.path().join("blocked");
+        fs::write(&blocked, "").unwrap();
+        let service = GitService::open_repo(path)
+            .unwrap()
+            .with_index_dir(Some(blocked));
+        assert_eq!(service.get_commits(FROM, TO).unwrap().len(), 2);
+        let warnings = service.collection_warnings(FROM).unwrap();
+        assert!(matches!(
+            warnings.as_slice(),
+            [RepoWarning::IndexNotSaved { .. }]
+        ));
+    }
+
+    #[test]
+    fn test_diff_summary_lists_hunks_and_changed_declarations() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let commit_file = |content: &str| {
+            commit_at(
+                &repo,
+                ALICE,
+                "change",
+                FROM,
+                FROM,
+                &[("lib.rs", Some(content))],
+            )
+        };
+
+        commit_file("pub fn keep() {}\n\nfn old_helper() {}\n");
+        let oid = commit_file("pub fn keep() {\n    1\n}\n\nstruct NewType;\n");
+
+        let service = GitService::open_repo(dir.path().to_str().unwrap()).unwrap();
+        let summary = service
+            .summarize_commit_diff(&oid.to_string(), &[], 0)
+            .unwrap();
+
+        assert!(summary.summarized);
+        let file = &summary.files[0];
+        assert_eq!(file.path, "lib.rs");
+        assert_eq!(file.added_symbols, vec!["NewType"]);
+        assert_eq!(file.removed_symbols, vec!["old_helper"]);
+        assert!(summary.text.contains("- lib.rs (modified, +4 -2)"));
+        assert!(summary.text.contains(&file.hunks[0].header()));
+
+        // 未超过阈值时返回完整 diff
+        let full = service
+            .summarize_commit_diff(&oid.to_string(), &[], usize::MAX)
+            .unwrap();
+        assert!(!full.summarized);
+        assert!(full.text.contains("+struct NewType;"));
+    }
+
+    #[test]
+    fn test_hotspots_roll_up_directories_and_skip_duplicated_merges() {
+        let commit = |hash: &str, email: &str, parents: &[&str], files: &[(&str, usize)]| {
+            serde_json::from_value::<Commit>(serde_json::json!({
+                "hash": hash,
+                "author": email,
+                "email": email,
+                "timestamp": FROM,
+                "message": "change",
+                "parents": parents,
+                "files": files.iter().map(|(path, lines)| serde_json::json!({
+                    "path": path,
+                    "kind": "modified",
+                    "insertions": lines,
+                    "deletions": 0,
+                    "binary": false,
+                })).collect::<Vec<_>>(),
+            }))
+            .unwrap()
+        };
+
+        let commits = vec![
+            commit(
+                "a",
+                "alice@example.com",
+                &["0"],
+                &[("src/app/main.rs", 5), ("README.md", 1)],
+            ),
+            commit(
+                "b",
+                "bob@example.com",
+                &["a"],
+                &[("src/app/main.rs", 2), ("src/lib.rs", 9)],
+            ),
+            // 被合并提交 b 已在列表中，合并提交不再重复计数
+            commit(
+                "m",
+                "alice@example.com",
+                &["a", "b"],
+                &[("src/app/main.rs", 2), ("src/lib.rs", 9)],
+            ),
+        ];
+        let options = HotspotOptions { depth: 1, limit: 2 };
+        let hotspots = GitService::rank_hotspots(&commits, &options);
+
+        assert_eq!(hotspots.files.len(), 2);
+        assert_eq!(hotspots.files[0].path, "src/app/main.rs");
+        assert_eq!(hotspots.files[0].commits, 2);
+        assert_eq!(hotspots.files[0].authors, 2);
+        assert_eq!(hotspots.files[1].path, "src/lib.rs");
+
+        assert_eq!(hotspots.directories[0].path, "src");
+        assert_eq!(hotspots.directories[0].commits, 2);
+        assert_eq!(hotspots.directories[0].insertions, 16);
+        assert_eq!(hotspots.directories[1].path, ".");
+    }
+
+    #[test]
+    fn test_ownership_blames_surviving_lines_by_canonical_author() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit_at(
+            &repo,
+            ALICE,
+            "init",
+            FROM,
+            FROM,
+            &[
+                ("src/lib.rs", Some("a\nb\nc\nd\n")),
+                ("README.md", Some("hello\n")),
+            ],
+        );
+        // Bob 用旧邮箱改写了两行，别名表将其归并到新邮箱
+        commit_at(
+            &repo,
+            ("bob", "bob@old.example.com"),
+            "rewrite",
+            FROM,
+            FROM,
+            &[("src/lib.rs", Some("a\nB\nC\nd\n"))],
+        );
+        commit_at(
+            &repo,
+            BOB,
+            "docs",
+            FROM,
+            FROM,
+            &[("docs/guide.md", Some("x\ny\n"))],
+        );
+
+        let service = GitService::open_repo(dir.path().to_str().unwrap())
+            .unwrap()
+            .with_author_aliases(vec![AuthorAlias {
+                name: "Bob".to_string(),
+                email: "bob@example.com".to_string(),
+                aliases: vec!["bob@old.example.com".to_string()],
+            }]);
+
+        let files = service
+            .ownership_candidates(&[], &OwnershipOptions::default())
+            .unwrap();
+        assert!(files.is_empty());
+
+        let options = OwnershipOptions {
+            paths: vec!["src".to_string(), "docs".to_string()],
+            ..Default::default()
+        };
+        let files = service.ownership_candidates(&[], &options).unwrap();
+        assert_eq!(files, vec!["docs/guide.md", "src/lib.rs"]);
+
+        let summary = service
+            .analyze_ownership(&files, Instant::now() + Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(summary.total_lines, 6);
+        assert_eq!(summary.files_blamed, 2);
+        assert!(!summary.truncated);
+        assert_eq!(summary.authors.len(), 2);
+        assert_eq!(summary.authors[0].email, "bob@example.com");
+        assert_eq!(summary.authors[0].lines, 4);
+        assert_eq!(summary.authors[0].percent, 66.7);
+        assert_eq!(summary.directories[0].path, "src");
+        assert_eq!(summary.directories[0].owners[0].lines, 2);
+
+        // 时间预算用完时剩余文件计为跳过
+        let summary = service.analyze_ownership(&files, Instant::now()).unwrap();
+        assert!(summary.truncated);
+        assert_eq!(summary.files_skipped, 2);
+        assert_eq!(summary.files_blamed, 0);
+    }
+
+    #[test]
+    fn test_activity_stats_use_configured_timezone() {
+        let commit = |timestamp: i64| {
+            serde_json::from_value::<Commit>(serde_json::json!({
+                "hash": timestamp.to_string(),
+                "author": "Alice",
+                "email": "alice@example.com",
+                "timestamp": timestamp,
+                "message": "change",
+            }))
+            .unwrap()
+        };
+        // FROM 为周一 00:00 UTC
+        let commits = vec![
+            commit(FROM + 2 * 3600),           // 周一 10:00 +08:00
+            commit(FROM + 14 * 3600),          // 周一 22:00 +08:00
+            commit(FROM + DAY + 3 * 3600),     // 周二 11:00 +08:00
+            commit(FROM + 5 * DAY + 4 * 3600), // 周六 12:00 +08:00
+        ];
+
+        let timezone = TimeZone::parse("Asia/Shanghai").unwrap();
+        let activity = GitService::activity_stats(&commits, &timezone);
+
+        assert_eq!(activity.by_weekday, vec![2, 1, 0, 0, 0, 1, 0]);
+        assert_eq!(activity.by_hour[22], 1);
+        assert_eq!(activity.heatmap[0][10], 1);
+        assert_eq!(activity.active_days, 3);
+        assert_eq!(activity.span_days, 6);
+        assert_eq!(activity.longest_streak, 2);
+        assert_eq!(activity.off_hours_commits, 1);
+        assert_eq!(activity.weekend_commits, 1);
+        assert_eq!(activity.days[0].date, "2024-01-01");
+        assert_eq!(activity.days[0].first_time, "10:00");
+        assert_eq!(activity.days[0].last_time, "22:00");
+
+        // 无法识别的时区不影响打开仓库，按 UTC 统计并给出告警
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit_at(&repo, ALICE, "init", FROM, FROM, &[]);
+        let config = AppConfig {
+            timezone: "Mars/Olympus".to_string(),
+            ..Default::default()
+        };
+        let service =
+            GitService::open_repo_with_config(dir.path().to_str().unwrap(), &config).unwrap();
+        assert_eq!(service.timezone, TimeZone::utc());
+        assert_eq!(
+            service.collection_warnings(FROM).unwrap(),
+            vec![RepoWarning::UnknownTimezone {
+                name: "Mars/Olympus".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_submodules_inherit_the_full_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let parent = Repository::init(dir.path()).unwrap();
+        commit_at(&parent, ALICE, "init", FROM, FROM, &[]);
+
+        let mut submodule = parent
+            .submodule("https://example.com/ui.git", Path::new("ui"), false)
+            .unwrap();
+        commit_at(&submodule.open().unwrap(), BOB, "init ui", FROM, FROM, &[]);
+        submodule.add_finalize().unwrap();
+
+        let config = AppConfig {
+            author_aliases: vec![AuthorAlias {
+                name: "Robert".to_string(),
+                email: "robert@example.com".to_string(),
+                aliases: vec!["bob@example.com".to_string()],
+            }],
+            language_mappings: vec![LanguageMapping {
+                pattern: "*.hbs".to_string(),
+                language: "Handlebars".to_string(),
+                category: None,
+            }],
+            timezone: "Asia/Shanghai".to_string(),
+            ..Default::default()
+        };
+        let service =
+            GitService::open_repo_with_config(dir.path().to_str().unwrap(), &config).unwrap();
+        let submodules = service.open_submodules().unwrap();
+
+        assert_eq!(submodules.len(), 1);
+        let (path, child) = &submodules[0];
+        assert_eq!(path, "ui");
+        assert_eq!(child.languages.classify("views/page.hbs").1, "Handlebars");
+        assert_eq!(child.timezone.name(), "Asia/Shanghai");
+        assert_eq!(child.identity.aliases.len(), 1);
+        assert_eq!(child.identity.aliases[0].name, "Robert");
+    }
+
+    #[test]
+    fn test_submodule_groups_nest_and_link_pointer_updates() {
+        /// 提交 .gitmodules 与子模块指针（gitlink）
+        fn pin(repo: &Repository, message: &str, time: i64, path: &str, target: Oid) -> Oid {
+            let signature = Signature::new(ALICE.0, ALICE.1, &Time::new(time, 0)).unwrap();
+            let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+            let base = match &parent {
+                Some(parent) => parent.tree().unwrap(),
+                None => repo
+                    .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+                    .unwrap(),
+            };
+            let gitmodules = fs::read(repo.workdir().unwrap().join(".gitmodules")).unwrap();
+
+            let mut update = git2::build::TreeUpdateBuilder::new();
+            update.upsert(
+                ".gitmodules",
+                repo.blob(&gitmodules).unwrap(),
+                git2::FileMode::Blob,
+            );
+            update.upsert(path, target, git2::FileMode::Commit);
+            let tree = repo
+                .find_tree(update.create_updated(repo, &base).unwrap())
+                .unwrap();
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+
+            repo.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents,
+            )
+            .unwrap()
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let parent = Repository::init(dir.path()).unwrap();
+        let ui = parent
+            .submodule("https://example.com/ui.git", Path::new("ui"), false)
+            .unwrap()
+            .open()
+            .unwrap();
+        let ui_init = commit_at(&ui, BOB, "init ui", FROM - 10 * DAY, FROM - 10 * DAY, &[]);
+        let pinned = pin(&parent, "add ui", FROM - 10 * DAY, "ui", ui_init);
+
+        // 子模块中再嵌套一个子模块，窗口内均有提交
+        let icons = ui
+            .submodule("https://example.com/icons.git", Path::new("icons"), false)
+            .unwrap()
+            .open()
+            .unwrap();
+        let icons_init = commit_at(&icons, BOB, "init icons", FROM + DAY, FROM + DAY, &[]);
+        let ui_style = commit_at(
+            &ui,
+            BOB,
+            "style buttons",
+            FROM + DAY,
+            FROM + DAY,
+            &[("button.css", Some("a {}\n"))],
+        );
+        let ui_icons = pin(&ui, "add icons", FROM + 2 * DAY, "icons", icons_init);
+        let bumped = pin(&parent, "bump ui", FROM + 3 * DAY, "ui", ui_icons);
+
+        let service = GitService::open_repo(dir.path().to_str().unwrap()).unwrap();
+        let messages = |group: &RepoGroup| -> Vec<String> {
+            group.commits.iter().map(|c| c.message.clone()).collect()
+        };
+
+        let groups = service
+            .get_submodule_groups(FROM, TO, &CommitFilter::default())
+            .unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].repo_name, "ui");
+        assert_eq!(messages(&groups[0]), vec!["add icons", "style buttons"]);
+        assert!(groups[0].warnings.is_empty());
+        assert_eq!(groups[0].submodules.len(), 1);
+        assert_eq!(groups[0].submodules[0].repo_name, "icons");
+        assert_eq!(messages(&groups[0].submodules[0]), vec!["init icons"]);
+
+        let filter = CommitFilter {
+            include_submodules: true,
+            ..Default::default()
+        };
+        let commits = service.get_commits_filtered(FROM, TO, &filter).unwrap();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].hash, bumped.to_string());
+        let updates = &commits[0].submodule_updates;
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].path, "ui");
+        assert_eq!(updates[0].from, Some(ui_init.to_string()));
+        assert_eq!(updates[0].to, Some(ui_icons.to_string()));
+        let mut linked = updates[0].commits.clone();
+        linked.sort();
+        let mut expected = vec![ui_style.to_string(), ui_icons.to_string()];
+        expected.sort();
+        assert_eq!(linked, expected);
+
+        // 窗口外的首次接入不在结果中
+        assert!(commits.iter().all(|c| c.hash != pinned.to_string()));
+    }
 }
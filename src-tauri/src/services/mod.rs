@@ -1,14 +1,22 @@
 // 服务模块
 
 pub mod cache_service;
+pub mod collect_service;
+pub mod commit_index;
+pub mod commit_parser;
+pub mod diff_summary;
 pub mod encryption_service;
 pub mod export_service;
+pub mod file_classifier;
 pub mod git_service;
 pub mod llm_service;
 pub mod report_service;
+pub mod session_estimator;
 pub mod storage_service;
 pub mod template_service;
+pub mod time_zone;
 
+pub use collect_service::CollectService;
 pub use encryption_service::EncryptionService;
 pub use export_service::ExportService;
 pub use git_service::GitService;
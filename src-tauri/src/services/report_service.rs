@@ -1,16 +1,35 @@
 // 报告服务：用 Handlebars 模板编排报告生成流程
 
-use crate::models::{Commit, RepoGroup, Report, ReportType, TemplateType};
-use crate::services::{llm_service::LLMService, template_service::TemplateService};
+use crate::models::{
+    AuthorAlias, Commit, ConventionalCommit, DiffInclusion, HotspotOptions, Hotspots,
+    LanguageMapping, LanguageShare, OwnershipOptions, RepoGroup, RepoWarning, Report, ReportType,
+    SessionOptions, TemplateType,
+};
+use crate::services::file_classifier::FileClassifier;
+use crate::services::git_service::PathMatcher;
+use crate::services::session_estimator::SessionEstimator;
+use crate::services::time_zone::TimeZone;
+use crate::services::{
+    commit_parser, llm_service::LLMService, template_service::TemplateService, GitService,
+};
 use handlebars::Handlebars;
-use serde_json::json;
-use std::collections::HashSet;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tauri::AppHandle;
 
 pub struct ReportService {
     llm_service: Arc<LLMService>,
     handlebars: Handlebars<'static>,
+    diff_inclusion: DiffInclusion,
+    hotspot_options: HotspotOptions,
+    languages: FileClassifier,
+    timezone: TimeZone,
+    session_options: SessionOptions,
+    /// 代码归属分析选项，为 None 时报告不含代码归属
+    ownership: Option<OwnershipOptions>,
+    author_aliases: Vec<AuthorAlias>,
 }
 
 impl ReportService {
@@ -28,13 +47,62 @@ impl ReportService {
         Self {
             llm_service,
             handlebars,
+            diff_inclusion: DiffInclusion::default(),
+            hotspot_options: HotspotOptions::default(),
+            languages: FileClassifier::default(),
+            timezone: TimeZone::utc(),
+            session_options: SessionOptions::default(),
+            ownership: None,
+            author_aliases: Vec::new(),
         }
     }
 
+    /// 设置提示词附带 diff 的方式（默认不附带）
+    pub fn with_diff_inclusion(mut self, diff_inclusion: DiffInclusion) -> Self {
+        self.diff_inclusion = diff_inclusion;
+        self
+    }
+
+    /// 设置改动热点的汇总层级与条目数
+    pub fn with_hotspot_options(mut self, hotspot_options: HotspotOptions) -> Self {
+        self.hotspot_options = hotspot_options;
+        self
+    }
+
+    /// 设置语言识别规则，优先于内置的扩展名 / 文件名表
+    pub fn with_language_mappings(mut self, mappings: &[LanguageMapping]) -> Result<Self, String> {
+        self.languages = FileClassifier::compile(mappings)?;
+        Ok(self)
+    }
+
+    /// 设置提交时间分布使用的时区；无法识别时按 UTC 统计（采集告警中已提示）
+    pub fn with_timezone(mut self, timezone: &str) -> Self {
+        self.timezone = TimeZone::parse_or_utc(timezone).0;
+        self
+    }
+
+    /// 设置工时估算的会话间隔上限与首个提交的准备时间
+    pub fn with_session_options(mut self, session_options: SessionOptions) -> Self {
+        self.session_options = session_options;
+        self
+    }
+
+    /// 开启代码归属分析（需要重新打开各仓库执行 blame，默认关闭）
+    pub fn with_ownership(mut self, ownership: Option<OwnershipOptions>) -> Self {
+        self.ownership = ownership;
+        self
+    }
+
+    /// 设置代码归属分析归并作者时使用的别名表
+    pub fn with_author_aliases(mut self, author_aliases: Vec<AuthorAlias>) -> Self {
+        self.author_aliases = author_aliases;
+        self
+    }
+
     /// 流式生成周报
     pub async fn generate_weekly(
         &self,
-        repo_groups: Vec<RepoGroup>,
+        mut repo_groups: Vec<RepoGroup>,
         template_id: Option<String>,
         app: Option<AppHandle>,
     ) -> Result<Report, String> {
@@ -43,34 +111,21 @@ impl ReportService {
         }
 
         // 展平所有提交用于统计
-        let all_commits: Vec<Commit> = repo_groups.iter().flat_map(|g| g.commits.clone()).collect();
+        let all_commits: Vec<Commit> = repo_groups
+            .iter()
+            .flat_map(RepoGroup::flatten)
+            .flat_map(|g| g.commits.clone())
+            .collect();
 
         if all_commits.is_empty() {
             return Err("No commits provided for report generation".to_string());
         }
 
-        let stats = self.calculate_stats(&all_commits);
-        let context = json!({
-            "repo_groups": repo_groups.iter().map(|group| json!({
-                "repo_name": &group.repo_name,
-                "commit_count": group.commits.len(),
-                "commits": group.commits.iter().map(|c| json!({
-                    "hash": &c.hash[..7.min(c.hash.len())],
-                    "message": &c.message,
-                    "author": &c.author,
-                    "timestamp": format_timestamp(c.timestamp),
-                })).collect::<Vec<_>>(),
-            })).collect::<Vec<_>>(),
-            "total_repos": repo_groups.len(),
-            "total_commits": all_commits.len(),
-            "date_range": format!(
-                "{} - {}",
-                format_timestamp(all_commits.iter().map(|c| c.timestamp).min().unwrap_or(0)),
-                format_timestamp(all_commits.iter().map(|c| c.timestamp).max().unwrap_or(0))
-            ),
-            "unique_authors": stats.unique_authors,
-            "files_changed": stats.total_files_changed,
-        });
+        // diff 只进入提示词，不随报告返回
+        self.attach_diffs(&mut repo_groups).await;
+
+        let ownership = self.ownership_context(&repo_groups).await;
+        let context = self.build_context(&repo_groups, &all_commits, ownership);
 
         // 读取模板内容
         let template_content = if let Some(tid) = template_id {
@@ -105,13 +160,15 @@ impl ReportService {
             generated_at: chrono::Utc::now().timestamp(),
             content,
             commits: all_commits.clone(),
+            warnings: collect_warnings(&repo_groups),
+            incomplete: is_incomplete(&repo_groups),
         })
     }
 
     /// 流式生成月报
     pub async fn generate_monthly(
         &self,
-        repo_groups: Vec<RepoGroup>,
+        mut repo_groups: Vec<RepoGroup>,
         template_id: Option<String>,
         app: Option<AppHandle>,
     ) -> Result<Report, String> {
@@ -120,37 +177,24 @@ impl ReportService {
         }
 
         // 展平所有提交用于统计
-        let all_commits: Vec<Commit> = repo_groups.iter().flat_map(|g| g.commits.clone()).collect();
+        let all_commits: Vec<Commit> = repo_groups
+            .iter()
+            .flat_map(RepoGroup::flatten)
+            .flat_map(|g| g.commits.clone())
+            .collect();
 
         if all_commits.is_empty() {
             return Err("No commits provided for report generation".to_string());
         }
 
-        let stats = self.calculate_stats(&all_commits);
+        // diff 只进入提示词，不随报告返回
+        self.attach_diffs(&mut repo_groups).await;
+
         let commits_by_week = self.group_commits_by_week(&all_commits);
 
-        let context = json!({
-            "repo_groups": repo_groups.iter().map(|group| json!({
-                "repo_name": &group.repo_name,
-                "commit_count": group.commits.len(),
-                "commits": group.commits.iter().map(|c| json!({
-                    "hash": &c.hash[..7.min(c.hash.len())],
-                    "message": &c.message,
-                    "author": &c.author,
-                    "timestamp": format_timestamp(c.timestamp),
-                })).collect::<Vec<_>>(),
-            })).collect::<Vec<_>>(),
-            "total_repos": repo_groups.len(),
-            "total_commits": all_commits.len(),
-            "date_range": format!(
-                "{} - {}",
-                format_timestamp(all_commits.iter().map(|c| c.timestamp).min().unwrap_or(0)),
-                format_timestamp(all_commits.iter().map(|c| c.timestamp).max().unwrap_or(0))
-            ),
-            "unique_authors": stats.unique_authors,
-            "files_changed": stats.total_files_changed,
-            "weeks_count": commits_by_week.len(),
-        });
+        let ownership = self.ownership_context(&repo_groups).await;
+        let mut context = self.build_context(&repo_groups, &all_commits, ownership);
+        context["weeks_count"] = json!(commits_by_week.len());
 
         // 读取模板内容
         let template_content = if let Some(tid) = template_id {
@@ -185,19 +229,208 @@ impl ReportService {
             generated_at: chrono::Utc::now().timestamp(),
             content,
             commits: all_commits.clone(),
+            warnings: collect_warnings(&repo_groups),
+            incomplete: is_incomplete(&repo_groups),
         })
     }
 
+    /// 构建周报 / 月报共用的模板上下文；代码归属需要 blame，由调用方预先算好
+    fn build_context(
+        &self,
+        repo_groups: &[RepoGroup],
+        all_commits: &[Commit],
+        ownership: Vec<Value>,
+    ) -> Value {
+        let stats = self.calculate_stats(all_commits);
+        let work_hours = SessionEstimator::new(self.session_options.clone(), self.timezone.clone())
+            .estimate(repo_groups);
+        let group_context = |group: &RepoGroup| {
+            let mut context = repo_group_context(group);
+            context["languages"] = json!(self.language_breakdown(&group.commits));
+            context
+        };
+
+        json!({
+            "repo_groups": repo_groups.iter().map(|group| {
+                let mut context = group_context(group);
+                // 嵌套子模块展平为一层
+                context["submodules"] = json!(group
+                    .flatten()
+                    .into_iter()
+                    .skip(1)
+                    .map(group_context)
+                    .collect::<Vec<_>>());
+                context
+            }).collect::<Vec<_>>(),
+            "total_repos": repo_groups.iter().map(|g| g.flatten().len()).sum::<usize>(),
+            "total_commits": all_commits.len(),
+            "date_range": format!(
+                "{} - {}",
+                format_timestamp(all_commits.iter().map(|c| c.timestamp).min().unwrap_or(0)),
+                format_timestamp(all_commits.iter().map(|c| c.timestamp).max().unwrap_or(0))
+            ),
+            "unique_authors": stats.unique_authors,
+            "author_stats": author_stats(all_commits),
+            "files_changed": stats.total_files_changed,
+            "languages": stats.languages,
+            "activity": GitService::activity_stats(all_commits, &self.timezone),
+            "work_hours": work_hours,
+            "commits_by_type": group_commits_by_type(repo_groups),
+            "breaking_changes": collect_breaking_changes(repo_groups),
+            "tickets": group_commits_by_ticket(repo_groups),
+            "hotspots": self.rank_hotspots(repo_groups),
+            "ownership": ownership,
+            "warnings": collect_warnings(repo_groups),
+            "incomplete": is_incomplete(repo_groups),
+            "diffs_included": repo_groups
+                .iter()
+                .flat_map(RepoGroup::flatten)
+                .any(|g| g.commits.iter().any(|c| c.diff.is_some())),
+        })
+    }
+
+    /// 汇总全部仓库的改动热点；多个仓库时路径前加仓库名，目录汇总相应多一层
+    fn rank_hotspots(&self, repo_groups: &[RepoGroup]) -> Hotspots {
+        let groups: Vec<&RepoGroup> = repo_groups.iter().flat_map(RepoGroup::flatten).collect();
+        if groups.len() == 1 {
+            return GitService::rank_hotspots(&groups[0].commits, &self.hotspot_options);
+        }
+
+        let commits: Vec<Commit> = groups
+            .iter()
+            .flat_map(|group| {
+                group.commits.iter().map(|commit| {
+                    let mut commit = commit.clone();
+                    for file in commit.files.iter_mut().flatten() {
+                        file.path = format!("{}/{}", group.repo_name, file.path);
+                    }
+                    commit
+                })
+            })
+            .collect();
+
+        let options = HotspotOptions {
+            depth: self.hotspot_options.depth + 1,
+            ..self.hotspot_options.clone()
+        };
+        GitService::rank_hotspots(&commits, &options)
+    }
+
+    /// 各仓库（含子模块）的代码归属，所有仓库共用一个时间预算
+    /// 仓库无法打开或 blame 失败时跳过该仓库，不影响报告生成
+    async fn ownership_context(&self, repo_groups: &[RepoGroup]) -> Vec<Value> {
+        let Some(options) = self.ownership.clone() else {
+            return Vec::new();
+        };
+        let author_aliases = self.author_aliases.clone();
+        let groups: Vec<(String, String, Vec<Commit>)> = repo_groups
+            .iter()
+            .flat_map(RepoGroup::flatten)
+            .map(|group| {
+                (
+                    group.repo_name.clone(),
+                    group.repo_path.clone(),
+                    group.commits.clone(),
+                )
+            })
+            .collect();
+
+        // blame 是阻塞的 git2 调用，与采集一样放到阻塞线程池执行
+        tokio::task::spawn_blocking(move || ownership_summaries(&groups, &options, &author_aliases))
+            .await
+            .unwrap_or_default()
+    }
+
+    /// 按字符预算为提交附带 diff（开启时）
+    /// 预算在仓库间均分，用不完的份额顺延给后面的仓库；仓库内按有效变更行数从小到大分配，
+    /// 放不下完整 diff 的提交退回逐文件统计
+    async fn attach_diffs(&self, repo_groups: &mut [RepoGroup]) {
+        let options = self.diff_inclusion.clone();
+        if !options.enabled || options.max_chars == 0 {
+            return;
+        }
+
+        let excludes: Vec<String> = options
+            .exclude_paths
+            .iter()
+            .map(|p| format!("!{}", p))
+            .collect();
+        let Ok(matcher) = PathMatcher::new(&excludes) else {
+            return;
+        };
+
+        // 每个仓库的候选提交，按有效变更行数总和从小到大排列
+        let mut repos: Vec<(&str, Vec<(&Commit, usize)>)> = repo_groups
+            .iter()
+            .flat_map(RepoGroup::flatten)
+            .map(|group| {
+                (
+                    group.repo_path.as_str(),
+                    diff_candidates(&group.commits, &matcher),
+                )
+            })
+            .filter(|(_, candidates)| !candidates.is_empty())
+            .collect();
+        repos.sort_by_key(|(_, candidates)| candidates.iter().map(|(_, size)| size).sum::<usize>());
+
+        let repos: Vec<DiffCandidates> = repos
+            .into_iter()
+            .map(|(repo_path, candidates)| DiffCandidates {
+                repo_path: repo_path.to_string(),
+                commits: candidates
+                    .into_iter()
+                    .map(|(commit, _)| (commit.hash.clone(), file_stat_summary(commit, &matcher)))
+                    .collect(),
+            })
+            .collect();
+
+        let repo_paths: HashSet<String> = repos.iter().map(|r| r.repo_path.clone()).collect();
+
+        // 读取 diff 是阻塞的 git2 调用，与采集一样放到阻塞线程池执行
+        let allocated = tokio::task::spawn_blocking(move || {
+            allocate_diffs(
+                &repos,
+                &excludes,
+                options.max_chars,
+                options.summary_threshold,
+            )
+        })
+        .await
+        .map_err(|e| format!("Diff allocation task failed: {}", e));
+
+        match allocated {
+            Ok(mut diffs) => apply_diffs(repo_groups, &mut diffs),
+            // 任务失败时报告照常生成，在涉及的仓库上记录告警
+            Err(message) => mark_failed(repo_groups, &repo_paths, &message),
+        }
+    }
+
     /// 根据提交计算统计信息
     fn calculate_stats(&self, commits: &[Commit]) -> ReportStats {
-        let unique_authors: HashSet<_> = commits.iter().map(|c| c.author.clone()).collect();
+        // 结对提交的合作者同样计入作者
+        let unique_authors: HashSet<_> =
+            commits.iter().flat_map(|c| c.participant_keys()).collect();
+
+        // 按路径去重统计涉及的文件（依赖采集时附带的文件明细）
+        let unique_files: HashSet<_> = commits
+            .iter()
+            .flat_map(|c| c.files.iter().flatten())
+            .map(|f| f.path.as_str())
+            .collect();
 
         ReportStats {
             unique_authors: unique_authors.len(),
-            total_files_changed: 0, // 待办：计算文件变更数（需要 diff 信息）
+            total_files_changed: unique_files.len(),
+            languages: self.language_breakdown(commits),
         }
     }
 
+    /// 按语言 / 文件类型划分变更行数
+    fn language_breakdown(&self, commits: &[Commit]) -> Vec<LanguageShare> {
+        self.languages
+            .breakdown(commits.iter().flat_map(|c| c.files.iter().flatten()))
+    }
+
     /// 按周聚合提交
     fn group_commits_by_week<'a>(&self, commits: &'a [Commit]) -> Vec<Vec<&'a Commit>> {
         use chrono::{DateTime, Datelike, Utc};
@@ -222,6 +455,378 @@ impl ReportService {
 struct ReportStats {
     unique_authors: usize,
     total_files_changed: usize,
+    languages: Vec<LanguageShare>,
+}
+
+/// 单个仓库分组的模板上下文（不含子模块）
+fn repo_group_context(group: &RepoGroup) -> Value {
+    json!({
+        "repo_name": &group.repo_name,
+        "commit_count": group.commits.len(),
+        "commits": group.commits.iter().map(commit_context).collect::<Vec<_>>(),
+        "releases": group.releases.iter().map(|r| json!({
+            "name": &r.name,
+            "commit": short_hash(&r.target),
+            "annotated": r.annotated,
+            "tagger": &r.tagger,
+            "date": format_timestamp(r.timestamp),
+            "message": &r.message,
+            "previous_tag": &r.previous_tag,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+/// 单个提交的模板上下文
+fn commit_context(c: &Commit) -> Value {
+    let conventional = conventional_of(c);
+
+    json!({
+        "hash": short_hash(&c.hash),
+        "message": &c.message,
+        "author": &c.author,
+        "co_authors": c.co_authors.iter().map(|co| co.name.as_str()).collect::<Vec<_>>().join(", "),
+        "timestamp": format_timestamp(c.timestamp),
+        "branches": c.branches.join(", "),
+        "is_merge": c.is_merge(),
+        "type": &conventional.commit_type,
+        "scope": &conventional.scope,
+        "breaking": conventional.breaking,
+        "tickets": c.references.iter().map(|r| r.id.as_str()).collect::<Vec<_>>().join(", "),
+        "diff": &c.diff,
+        "files": c.files.iter().flatten().map(|f| json!({
+            "path": &f.path,
+            "old_path": &f.old_path,
+            "kind": f.kind,
+            "insertions": f.insertions,
+            "deletions": f.deletions,
+            "binary": f.binary,
+        })).collect::<Vec<_>>(),
+        "insertions": c.insertions(),
+        "deletions": c.deletions(),
+        "submodule_updates": c.submodule_updates.iter().map(|u| json!({
+            "path": &u.path,
+            "from": u.from.as_deref().map(short_hash),
+            "to": u.to.as_deref().map(short_hash),
+            "count": u.commits.len(),
+        })).collect::<Vec<_>>(),
+        "merged_commits": c.merged_commits.iter().map(|m| json!({
+            "hash": short_hash(&m.hash),
+            "message": &m.message,
+            "author": &m.author,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+/// 逐个仓库执行代码归属分析：(仓库名, 仓库路径, 提交)
+fn ownership_summaries(
+    groups: &[(String, String, Vec<Commit>)],
+    options: &OwnershipOptions,
+    author_aliases: &[AuthorAlias],
+) -> Vec<Value> {
+    let deadline = Instant::now() + Duration::from_millis(options.time_budget_ms);
+
+    groups
+        .iter()
+        .filter_map(|(repo_name, repo_path, commits)| {
+            let service = GitService::open_repo(repo_path)
+                .ok()?
+                .with_author_aliases(author_aliases.to_vec());
+            let files = service.ownership_candidates(commits, options).ok()?;
+            let summary = service.analyze_ownership(&files, deadline).ok()?;
+            (summary.files_blamed > 0).then(|| {
+                json!({
+                    "repo_name": repo_name,
+                    "total_lines": summary.total_lines,
+                    "files_blamed": summary.files_blamed,
+                    "files_skipped": summary.files_skipped,
+                    "truncated": summary.truncated,
+                    "authors": summary.authors,
+                    "directories": summary.directories,
+                })
+            })
+        })
+        .collect()
+}
+
+/// 可附带 diff 的提交及其有效变更行数，小的在前
+/// 合并提交、缺少文件明细或只改动了排除路径与二进制文件的提交不参与
+fn diff_candidates<'a>(commits: &'a [Commit], matcher: &PathMatcher) -> Vec<(&'a Commit, usize)> {
+    let mut candidates: Vec<(&Commit, usize)> = commits
+        .iter()
+        .filter(|c| !c.is_merge())
+        .filter_map(|commit| {
+            let files: Vec<_> = commit
+                .files
+                .as_ref()?
+                .iter()
+                .filter(|f| !f.binary && matcher.matches(f))
+                .collect();
+            (!files.is_empty()).then(|| {
+                let size = files.iter().map(|f| f.insertions + f.deletions).sum();
+                (commit, size)
+            })
+        })
+        .collect();
+
+    candidates.sort_by_key(|(commit, size)| (*size, std::cmp::Reverse(commit.timestamp)));
+    candidates
+}
+
+/// 单个仓库中待附带 diff 的提交：(提交哈希, 逐文件统计)，已按分配顺序排列
+struct DiffCandidates {
+    repo_path: String,
+    commits: Vec<(String, String)>,
+}
+
+/// 在总预算内为各仓库的候选提交分配 diff，返回 (仓库路径, 提交哈希) -> diff 文本
+/// 同一提交可能出现在多个仓库（如 fork 或镜像），须按仓库区分
+fn allocate_diffs(
+    repos: &[DiffCandidates],
+    excludes: &[String],
+    max_chars: usize,
+    summary_threshold: usize,
+) -> HashMap<(String, String), String> {
+    let mut remaining = max_chars;
+    let mut diffs: HashMap<(String, String), String> = HashMap::new();
+
+    for (index, repo) in repos.iter().enumerate() {
+        let mut share = remaining / (repos.len() - index);
+        // 前端回传的仓库可能已被移动或删除，跳过即可
+        let Ok(git_service) = GitService::open_repo(&repo.repo_path) else {
+            continue;
+        };
+
+        for (hash, file_stats) in &repo.commits {
+            let full = git_service
+                .summarize_commit_diff(hash, excludes, summary_threshold)
+                .map(|summary| summary.text)
+                .unwrap_or_default();
+            let text = if !full.trim().is_empty() && full.len() <= share {
+                full
+            } else {
+                file_stats.clone()
+            };
+
+            if text.len() > share {
+                continue;
+            }
+            share -= text.len();
+            remaining -= text.len();
+            diffs.insert(
+                (repo.repo_path.clone(), hash.clone()),
+                text.trim_end().to_string(),
+            );
+        }
+    }
+
+    diffs
+}
+
+/// 逐文件变更统计，作为完整 diff 放不下时的替代
+fn file_stat_summary(commit: &Commit, matcher: &PathMatcher) -> String {
+    commit
+        .files
+        .iter()
+        .flatten()
+        .filter(|f| matcher.matches(f))
+        .map(|f| format!("{} (+{} -{})\n", f.path, f.insertions, f.deletions))
+        .collect()
+}
+
+/// 把分配好的 diff 写回提交（含子模块分组）
+fn apply_diffs(repo_groups: &mut [RepoGroup], diffs: &mut HashMap<(String, String), String>) {
+    for group in repo_groups {
+        for commit in &mut group.commits {
+            let key = (group.repo_path.clone(), commit.hash.clone());
+            if let Some(diff) = diffs.remove(&key) {
+                commit.diff = Some(diff);
+            }
+        }
+        apply_diffs(&mut group.submodules, diffs);
+    }
+}
+
+/// 在给定路径的仓库（含子模块分组）上记录失败告警
+fn mark_failed(repo_groups: &mut [RepoGroup], repo_paths: &HashSet<String>, message: &str) {
+    for group in repo_groups {
+        if repo_paths.contains(&group.repo_path) {
+            group.warnings.push(RepoWarning::Failed {
+                message: message.to_string(),
+            });
+        }
+        mark_failed(&mut group.submodules, repo_paths, message);
+    }
+}
+
+/// 读取提交的规范化类型信息；前端回传的旧数据缺失时现场解析
+fn conventional_of(commit: &Commit) -> ConventionalCommit {
+    commit
+        .conventional
+        .clone()
+        .unwrap_or_else(|| commit_parser::parse_conventional(&commit.message))
+}
+
+/// 按 Conventional Commits 类型分组，顺序遵循 TYPE_ORDER
+fn group_commits_by_type(repo_groups: &[RepoGroup]) -> Vec<Value> {
+    let mut groups: BTreeMap<(usize, String), Vec<Value>> = BTreeMap::new();
+
+    for group in repo_groups.iter().flat_map(RepoGroup::flatten) {
+        for commit in &group.commits {
+            let conventional = conventional_of(commit);
+            let order = commit_parser::TYPE_ORDER
+                .iter()
+                .position(|t| *t == conventional.commit_type)
+                .unwrap_or(commit_parser::TYPE_ORDER.len());
+
+            groups
+                .entry((order, conventional.commit_type.clone()))
+                .or_default()
+                .push(json!({
+                    "hash": short_hash(&commit.hash),
+                    "repo_name": &group.repo_name,
+                    "scope": &conventional.scope,
+                    "description": &conventional.description,
+                    "author": &commit.author,
+                    "breaking": conventional.breaking,
+                }));
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|((_, commit_type), commits)| {
+            json!({
+                "type": &commit_type,
+                "label": commit_parser::type_label(&commit_type),
+                "count": commits.len(),
+                "commits": commits,
+            })
+        })
+        .collect()
+}
+
+/// 汇总破坏性变更
+fn collect_breaking_changes(repo_groups: &[RepoGroup]) -> Vec<Value> {
+    repo_groups
+        .iter()
+        .flat_map(RepoGroup::flatten)
+        .flat_map(|group| {
+            group.commits.iter().filter_map(move |commit| {
+                let conventional = conventional_of(commit);
+                conventional.breaking.then(|| {
+                    json!({
+                        "hash": short_hash(&commit.hash),
+                        "repo_name": &group.repo_name,
+                        "type": &conventional.commit_type,
+                        "scope": &conventional.scope,
+                        "description": &conventional.description,
+                        "note": &conventional.breaking_note,
+                        "author": &commit.author,
+                    })
+                })
+            })
+        })
+        .collect()
+}
+
+/// 按工单分组提交，提交数多的工单在前
+fn group_commits_by_ticket(repo_groups: &[RepoGroup]) -> Vec<Value> {
+    // 工单标识 -> (规则名称, 提交列表)；BTreeMap 保证同数量时按标识排序
+    let mut tickets: BTreeMap<&str, (&str, Vec<Value>)> = BTreeMap::new();
+
+    for group in repo_groups.iter().flat_map(RepoGroup::flatten) {
+        for commit in &group.commits {
+            for reference in &commit.references {
+                tickets
+                    .entry(reference.id.as_str())
+                    .or_insert_with(|| (reference.kind.as_str(), Vec::new()))
+                    .1
+                    .push(json!({
+                        "hash": short_hash(&commit.hash),
+                        "repo_name": &group.repo_name,
+                        "message": commit.message.lines().next().unwrap_or(""),
+                        "author": &commit.author,
+                    }));
+            }
+        }
+    }
+
+    let mut tickets: Vec<_> = tickets.into_iter().collect();
+    tickets.sort_by_key(|(_, (_, commits))| std::cmp::Reverse(commits.len()));
+
+    tickets
+        .into_iter()
+        .map(|(id, (kind, commits))| {
+            json!({
+                "id": id,
+                "kind": kind,
+                "count": commits.len(),
+                "commits": commits,
+            })
+        })
+        .collect()
+}
+
+/// 按作者统计提交数：结对提交为作者与每位合作者各计一次，提交多的在前
+fn author_stats(commits: &[Commit]) -> Vec<Value> {
+    // 去重键 -> (展示名, 提交数, 其中作为合作者的提交数)
+    let mut authors: BTreeMap<String, (String, usize, usize)> = BTreeMap::new();
+
+    for commit in commits {
+        let entry = authors
+            .entry(commit.author_key())
+            .or_insert_with(|| (commit.author.clone(), 0, 0));
+        entry.1 += 1;
+
+        for co in &commit.co_authors {
+            let entry = authors
+                .entry(co.key())
+                .or_insert_with(|| (co.name.clone(), 0, 0));
+            entry.1 += 1;
+            entry.2 += 1;
+        }
+    }
+
+    let mut authors: Vec<_> = authors.into_values().collect();
+    authors.sort_by_key(|(_, commits, _)| std::cmp::Reverse(*commits));
+
+    authors
+        .into_iter()
+        .map(|(name, commits, co_authored)| {
+            json!({
+                "name": name,
+                "commits": commits,
+                "co_authored": co_authored,
+            })
+        })
+        .collect()
+}
+
+/// 汇总各仓库（含子模块）的采集告警
+fn collect_warnings(repo_groups: &[RepoGroup]) -> Vec<String> {
+    repo_groups
+        .iter()
+        .flat_map(RepoGroup::flatten)
+        .flat_map(|group| {
+            group
+                .warnings
+                .iter()
+                .map(move |w| format!("{}：{}", group.repo_name, w.describe()))
+        })
+        .collect()
+}
+
+/// 是否有仓库（含子模块）的数据不完整
+fn is_incomplete(repo_groups: &[RepoGroup]) -> bool {
+    repo_groups
+        .iter()
+        .flat_map(RepoGroup::flatten)
+        .any(|group| group.warnings.iter().any(RepoWarning::is_incomplete))
+}
+
+/// 截取 7 位短哈希
+fn short_hash(hash: &str) -> &str {
+    &hash[..7.min(hash.len())]
 }
 
 /// 将 Unix 时间戳格式化为可读日期
@@ -232,3 +837,124 @@ fn format_timestamp(timestamp: i64) -> String {
         .map(|dt| dt.format("%Y-%m-%d").to_string())
         .unwrap_or_else(|| "Unknown date".to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::diff_summary;
+    use git2::{Oid, Repository, Signature, Time};
+
+    const T0: i64 = 1_704_067_200;
+
+    /// 在 HEAD 上追加一个提交，写入仓库根目录下的单个文件
+    fn commit_file(repo: &Repository, message: &str, path: &str, content: &str) -> Oid {
+        let signature = Signature::new("Alice", "alice@example.com", &Time::new(T0, 0)).unwrap();
+        let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+        let base = parent.as_ref().map(|p| p.tree().unwrap());
+        let mut builder = repo.treebuilder(base.as_ref()).unwrap();
+        let blob = repo.blob(content.as_bytes()).unwrap();
+        builder.insert(path, blob, 0o100644).unwrap();
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+    }
+
+    /// 固定宽度的多行文件内容，每行 9 个字符
+    fn lines(count: usize) -> String {
+        (0..count).map(|i| format!("line {:03}\n", i)).collect()
+    }
+
+    #[test]
+    fn test_diff_budget_is_split_per_repository_with_file_stat_fallback() {
+        let small_dir = tempfile::tempdir().unwrap();
+        let small_repo = Repository::init(small_dir.path()).unwrap();
+        let tiny = commit_file(&small_repo, "tiny", "a.txt", "a\n");
+        // 完整 diff 约 1.1K，放得进总预算但超出本仓库的份额
+        let medium_content = lines(100);
+        let medium = commit_file(&small_repo, "medium", "mid.txt", medium_content.as_str());
+
+        let large_dir = tempfile::tempdir().unwrap();
+        let large_repo = Repository::init(large_dir.path()).unwrap();
+        let large_content = lines(500);
+        let large = commit_file(&large_repo, "large", "big.txt", large_content.as_str());
+
+        let candidates =
+            |dir: &tempfile::TempDir, commits: Vec<(git2::Oid, &str)>| DiffCandidates {
+                repo_path: dir.path().to_str().unwrap().to_string(),
+                commits: commits
+                    .into_iter()
+                    .map(|(oid, stats)| (oid.to_string(), stats.to_string()))
+                    .collect(),
+            };
+        let repos = vec![
+            candidates(
+                &small_dir,
+                vec![(tiny, "a.txt (+1 -0)\n"), (medium, "mid.txt (+100 -0)\n")],
+            ),
+            candidates(&large_dir, vec![(large, "big.txt (+500 -0)\n")]),
+        ];
+
+        let max_chars = 2000;
+        let diffs = allocate_diffs(&repos, &[], max_chars, diff_summary::DEFAULT_THRESHOLD);
+        let diff = |dir: &tempfile::TempDir, oid: git2::Oid| {
+            diffs[&(dir.path().to_str().unwrap().to_string(), oid.to_string())].clone()
+        };
+
+        // 小提交附带完整 diff，超出仓库份额的提交退回逐文件统计
+        assert!(diff(&small_dir, tiny).contains("+a"));
+        assert_eq!(diff(&small_dir, medium), "mid.txt (+100 -0)");
+        assert_eq!(diff(&large_dir, large), "big.txt (+500 -0)");
+
+        let small_total = diff(&small_dir, tiny).len() + diff(&small_dir, medium).len();
+        assert!(small_total <= max_chars / 2);
+        assert!(small_total + diff(&large_dir, large).len() <= max_chars);
+
+        // fork 中的同一提交按仓库各自分配，互不覆盖
+        let fork_dir = tempfile::tempdir().unwrap();
+        Repository::clone(small_dir.path().to_str().unwrap(), fork_dir.path()).unwrap();
+        let repos = vec![
+            candidates(&small_dir, vec![(tiny, "a.txt (+1 -0)\n")]),
+            candidates(&fork_dir, vec![(tiny, "a.txt (+1 -0)\n")]),
+        ];
+        let mut diffs = allocate_diffs(&repos, &[], max_chars, diff_summary::DEFAULT_THRESHOLD);
+        assert_eq!(diffs.len(), 2);
+
+        let mut groups: Vec<RepoGroup> = [&small_dir, &fork_dir]
+            .iter()
+            .map(|dir| {
+                let path = dir.path().to_str().unwrap().to_string();
+                let commit: Commit = serde_json::from_value(json!({
+                    "hash": tiny.to_string(),
+                    "author": "Alice",
+                    "email": "alice@example.com",
+                    "timestamp": T0,
+                    "message": "tiny",
+                }))
+                .unwrap();
+                RepoGroup {
+                    repo_id: path.clone(),
+                    repo_name: "app".to_string(),
+                    repo_path: path,
+                    commits: vec![commit],
+                    releases: Vec::new(),
+                    submodules: Vec::new(),
+                    warnings: Vec::new(),
+                }
+            })
+            .collect();
+        apply_diffs(&mut groups, &mut diffs);
+        assert!(groups.iter().all(|g| g.commits[0]
+            .diff
+            .as_deref()
+            .is_some_and(|d| d.contains("+a"))));
+    }
+}
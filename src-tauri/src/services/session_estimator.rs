@@ -0,0 +1,226 @@
+// 工时估算：把每位作者的提交时间聚类为工作会话（算法同 git-hours）
+//
+// 相邻提交间隔不超过上限时，间隔整体计入工时；超过上限则开启新会话，
+// 新会话的首个提交之前补计一段准备时间。只能反映大致投入，不是精确的考勤数据。
+
+use crate::models::{Commit, EstimatedHours, RepoGroup, SessionOptions, WorkEstimate};
+use crate::services::time_zone::TimeZone;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+pub struct SessionEstimator {
+    options: SessionOptions,
+    timezone: TimeZone,
+}
+
+/// 单个参与者的提交时刻：(提交时间, 仓库名)
+struct Timeline<'a> {
+    name: String,
+    moments: Vec<(i64, &'a str)>,
+}
+
+/// 某个分组累计的工时
+#[derive(Default)]
+struct Tally {
+    seconds: i64,
+    sessions: usize,
+    commits: usize,
+}
+
+impl Tally {
+    fn add(&mut self, seconds: i64, new_session: bool) {
+        self.seconds += seconds;
+        self.commits += 1;
+        if new_session {
+            self.sessions += 1;
+        }
+    }
+}
+
+impl SessionEstimator {
+    /// 日期按给定时区划分
+    pub fn new(options: SessionOptions, timezone: TimeZone) -> Self {
+        Self { options, timezone }
+    }
+
+    /// 估算全部仓库（含子模块）的工时
+    /// 同一作者在不同仓库的提交合并聚类，并行的工作不会重复计时；结对提交为每位参与者各计一次
+    pub fn estimate(&self, repo_groups: &[RepoGroup]) -> WorkEstimate {
+        // 参与者去重键 -> 提交时刻
+        let mut authors: HashMap<String, Timeline> = HashMap::new();
+        let mut seen: HashSet<(String, &str)> = HashSet::new();
+
+        for group in repo_groups.iter().flat_map(RepoGroup::flatten) {
+            // merges-only 策略下被合并的提交折叠在合并提交之下，同样是实际投入的时间
+            let commits = group
+                .commits
+                .iter()
+                .flat_map(|c| std::iter::once(c).chain(c.merged_commits.iter()));
+
+            for commit in commits {
+                for (key, name) in participants(commit) {
+                    if !seen.insert((key.clone(), commit.hash.as_str())) {
+                        continue;
+                    }
+                    authors
+                        .entry(key)
+                        .or_insert_with(|| Timeline {
+                            name,
+                            moments: Vec::new(),
+                        })
+                        .moments
+                        .push((commit.timestamp, group.repo_name.as_str()));
+                }
+            }
+        }
+
+        let max_gap = self.options.max_gap_minutes as i64 * 60;
+        let lead_time = self.options.first_commit_minutes as i64 * 60;
+
+        let mut total = Tally::default();
+        let mut by_day: BTreeMap<String, Tally> = BTreeMap::new();
+        let mut by_repo: HashMap<String, Tally> = HashMap::new();
+        let mut by_author: HashMap<String, Tally> = HashMap::new();
+
+        for Timeline { name, mut moments } in authors.into_values() {
+            moments.sort();
+
+            let mut previous: Option<i64> = None;
+            for (timestamp, repo) in moments {
+                // 间隔计入后一个提交所在的日期与仓库
+                let (seconds, new_session) = match previous {
+                    Some(previous) if timestamp - previous <= max_gap => {
+                        (timestamp - previous, false)
+                    }
+                    _ => (lead_time, true),
+                };
+                previous = Some(timestamp);
+
+                let day = self.timezone.local(timestamp).format("%Y-%m-%d");
+                for tally in [
+                    &mut total,
+                    by_day.entry(day.to_string()).or_default(),
+                    by_repo.entry(repo.to_string()).or_default(),
+                    by_author.entry(name.clone()).or_default(),
+                ] {
+                    tally.add(seconds, new_session);
+                }
+            }
+        }
+
+        WorkEstimate {
+            total_hours: hours(total.seconds),
+            sessions: total.sessions,
+            max_gap_minutes: self.options.max_gap_minutes,
+            first_commit_minutes: self.options.first_commit_minutes,
+            by_day: by_day.into_iter().map(estimated).collect(),
+            by_repo: ranked(by_repo),
+            by_author: ranked(by_author),
+        }
+    }
+}
+
+/// 作者与合作者的（去重键, 显示名）
+fn participants(commit: &Commit) -> Vec<(String, String)> {
+    std::iter::once((commit.author_key(), commit.author.clone()))
+        .chain(
+            commit
+                .co_authors
+                .iter()
+                .map(|co| (co.key(), co.name.clone())),
+        )
+        .collect()
+}
+
+fn estimated((name, tally): (String, Tally)) -> EstimatedHours {
+    EstimatedHours {
+        name,
+        hours: hours(tally.seconds),
+        sessions: tally.sessions,
+        commits: tally.commits,
+    }
+}
+
+/// 按工时从多到少排列
+fn ranked(tallies: HashMap<String, Tally>) -> Vec<EstimatedHours> {
+    let mut ranked: Vec<EstimatedHours> = tallies.into_iter().map(estimated).collect();
+    ranked.sort_by(|a, b| b.hours.total_cmp(&a.hours).then(a.name.cmp(&b.name)));
+    ranked
+}
+
+/// 秒换算为小时，保留一位小数
+fn hours(seconds: i64) -> f64 {
+    (seconds as f64 / 360.0).round() / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 3600;
+    const MONDAY: i64 = 1_704_067_200; // 2024-01-01 00:00:00 UTC
+
+    fn commit(hash: &str, author: &str, timestamp: i64) -> Commit {
+        serde_json::from_value(serde_json::json!({
+            "hash": hash,
+            "author": author,
+            "email": format!("{}@example.com", author.to_lowercase()),
+            "timestamp": timestamp,
+            "message": "change",
+        }))
+        .unwrap()
+    }
+
+    fn group(name: &str, commits: Vec<Commit>) -> RepoGroup {
+        RepoGroup {
+            repo_id: name.to_string(),
+            repo_name: name.to_string(),
+            repo_path: name.to_string(),
+            commits,
+            releases: Vec::new(),
+            submodules: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_sessions_cluster_commits_across_repositories() {
+        let repo_groups = vec![
+            group(
+                "app",
+                vec![
+                    // 9:00 开始的会话：首个提交补计 2 小时，随后 1 小时
+                    commit("a1", "Alice", MONDAY + 9 * HOUR),
+                    commit("a2", "Alice", MONDAY + 10 * HOUR),
+                    // 间隔超过 2 小时，开启新会话
+                    commit("a3", "Alice", MONDAY + 15 * HOUR),
+                    commit("b1", "Bob", MONDAY + 24 * HOUR + 9 * HOUR),
+                ],
+            ),
+            // 同一会话中切换到另一个仓库，30 分钟计入该仓库
+            group(
+                "lib",
+                vec![commit("l1", "Alice", MONDAY + 10 * HOUR + 1800)],
+            ),
+        ];
+
+        let estimate = SessionEstimator::new(SessionOptions::default(), TimeZone::utc())
+            .estimate(&repo_groups);
+
+        assert_eq!(estimate.total_hours, 7.5);
+        assert_eq!(estimate.sessions, 3);
+
+        assert_eq!(estimate.by_author[0].name, "Alice");
+        assert_eq!(estimate.by_author[0].hours, 5.5);
+        assert_eq!(estimate.by_author[0].sessions, 2);
+        assert_eq!(estimate.by_author[1].hours, 2.0);
+
+        assert_eq!(estimate.by_repo[0].name, "app");
+        assert_eq!(estimate.by_repo[0].hours, 7.0);
+        assert_eq!(estimate.by_repo[1].name, "lib");
+        assert_eq!(estimate.by_repo[1].hours, 0.5);
+
+        assert_eq!(estimate.by_day[0].name, "2024-01-01");
+        assert_eq!(estimate.by_day[0].hours, 5.5);
+        assert_eq!(estimate.by_day[1].name, "2024-01-02");
+    }
+}
@@ -0,0 +1,157 @@
+// 时区：解析 AppConfig.timezone，把 UTC 时间戳换算为当地时间
+//
+// 支持 UTC、固定偏移（如 +08:00、UTC-5）、系统本地时区与 IANA 时区名；
+// IANA 时区基于 chrono-tz 内置的 tz 数据库，历史上的夏令时规则变更也按当时的规则换算。
+
+use chrono::{DateTime, FixedOffset, Local, Offset, TimeZone as _};
+use chrono_tz::Tz;
+
+const HOUR: i32 = 3600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Zone {
+    Local,
+    Fixed(i32),
+    Named(Tz),
+}
+
+/// 配置中的时区
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeZone {
+    name: String,
+    zone: Zone,
+}
+
+impl Default for TimeZone {
+    fn default() -> Self {
+        Self::utc()
+    }
+}
+
+impl TimeZone {
+    pub fn utc() -> Self {
+        Self {
+            name: "UTC".to_string(),
+            zone: Zone::Fixed(0),
+        }
+    }
+
+    /// 解析时区名；不认识的名称报错，避免静默按 UTC 统计
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let trimmed = name.trim();
+        let zone = match trimmed {
+            "" | "UTC" | "utc" | "GMT" | "Z" => Zone::Fixed(0),
+            "Local" | "local" => Zone::Local,
+            _ => match trimmed.parse::<Tz>() {
+                Ok(tz) => Zone::Named(tz),
+                Err(_) => Zone::Fixed(
+                    parse_offset(trimmed)
+                        .ok_or_else(|| format!("Unsupported timezone '{}'", name))?,
+                ),
+            },
+        };
+
+        Ok(Self {
+            name: if trimmed.is_empty() { "UTC" } else { trimmed }.to_string(),
+            zone,
+        })
+    }
+
+    /// 解析时区名；无法识别时退回 UTC，并一并返回解析错误供调用方提示
+    pub fn parse_or_utc(name: &str) -> (Self, Option<String>) {
+        match Self::parse(name) {
+            Ok(timezone) => (timezone, None),
+            Err(e) => (Self::utc(), Some(e)),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 时间戳所在时刻的 UTC 偏移
+    pub fn offset_at(&self, timestamp: i64) -> FixedOffset {
+        let utc = DateTime::from_timestamp(timestamp, 0)
+            .unwrap_or_default()
+            .naive_utc();
+        match self.zone {
+            Zone::Local => Local.offset_from_utc_datetime(&utc).fix(),
+            Zone::Fixed(seconds) => {
+                FixedOffset::east_opt(seconds).unwrap_or_else(|| FixedOffset::east_opt(0).unwrap())
+            }
+            Zone::Named(tz) => tz.offset_from_utc_datetime(&utc).fix(),
+        }
+    }
+
+    /// 换算为当地时间
+    pub fn local(&self, timestamp: i64) -> DateTime<FixedOffset> {
+        let utc = DateTime::from_timestamp(timestamp, 0).unwrap_or_default();
+        utc.with_timezone(&self.offset_at(timestamp))
+    }
+}
+
+/// 解析 `+08:00`、`-0530`、`UTC+8`、`GMT-05:30` 形式的固定偏移
+fn parse_offset(value: &str) -> Option<i32> {
+    let value = value
+        .strip_prefix("UTC")
+        .or_else(|| value.strip_prefix("GMT"))
+        .unwrap_or(value);
+    let (sign, rest) = match value.chars().next()? {
+        '+' => (1, &value[1..]),
+        '-' => (-1, &value[1..]),
+        _ => return None,
+    };
+
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if rest.len() == 4 => rest.split_at(2),
+        None => (rest, "0"),
+    };
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 14 || minutes >= 60 {
+        return None;
+    }
+
+    Some(sign * (hours * HOUR + minutes * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 24 * 60 * 60;
+    const FROM: i64 = 1_704_067_200; // 2024-01-01 00:00:00 UTC
+
+    fn offset(name: &str, timestamp: i64) -> i32 {
+        TimeZone::parse(name)
+            .unwrap()
+            .offset_at(timestamp)
+            .local_minus_utc()
+    }
+
+    #[test]
+    fn test_named_zones_follow_the_tz_database() {
+        // 夏令时：纽约 7 月为 UTC-4，1 月为 UTC-5
+        assert_eq!(offset("America/New_York", FROM), -5 * HOUR);
+        assert_eq!(offset("America/New_York", FROM + 190 * DAY), -4 * HOUR);
+
+        // 2006-03-20 12:00 UTC：2007 年前美国夏令时从 4 月第一个周日开始，此时仍为 EST
+        assert_eq!(offset("America/New_York", 1_142_856_000), -5 * HOUR);
+
+        // 南半球的奥克兰 1 月为夏令时 UTC+13
+        assert_eq!(offset("Pacific/Auckland", FROM), 13 * HOUR);
+    }
+
+    #[test]
+    fn test_fixed_offsets_and_unknown_names() {
+        assert_eq!(offset("UTC+5:30", FROM), 19_800);
+        assert_eq!(offset("-05:00", FROM), -5 * HOUR);
+        assert_eq!(TimeZone::parse("").unwrap(), TimeZone::utc());
+
+        assert!(TimeZone::parse("Mars/Olympus").is_err());
+        let (timezone, error) = TimeZone::parse_or_utc("Mars/Olympus");
+        assert_eq!(timezone, TimeZone::utc());
+        assert!(error.is_some());
+    }
+}
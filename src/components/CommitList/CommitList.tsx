@@ -211,9 +211,17 @@ const CommitList = ({
         reportType === 'weekly' ? 'generate_weekly_report' : 'generate_monthly_report';
       const reportTypeName = reportType === 'weekly' ? t('周报') : t('月报');
 
+      // Requested window, used by the backend to detect history missing from shallow clones
+      const windowStart = dateRange?.from ? new Date(dateRange.from) : undefined;
+      windowStart?.setHours(0, 0, 0, 0);
+      const windowEnd = dateRange?.to ? new Date(dateRange.to) : undefined;
+      windowEnd?.setHours(23, 59, 59, 999);
+
       const report = await invoke<Report>(commandName, {
         repoGroups,
         templateId: selectedTemplateId || null,
+        from: windowStart ? Math.floor(windowStart.getTime() / 1000) : null,
+        to: windowEnd ? Math.floor(windowEnd.getTime() / 1000) : null,
       });
 
       const enrichedReport: Report = {
@@ -244,7 +252,10 @@ const CommitList = ({
 
   const uniqueAuthors = useMemo(() => {
     const authors = new Set<string>();
-    (allCommits || commits).forEach((c: any) => authors.add(c.author));
+    (allCommits || commits).forEach((c: any) => {
+      authors.add(c.author);
+      c.coAuthors?.forEach((co: { name: string }) => authors.add(co.name));
+    });
     return Array.from(authors).sort();
   }, [allCommits, commits]);
 
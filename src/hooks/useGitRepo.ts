@@ -1,8 +1,26 @@
 // Custom hook for Git repository operations
 
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { open } from '@tauri-apps/plugin-dialog';
-import type { RepoInfo, Commit } from '../types';
+import type {
+  RepoInfo,
+  Commit,
+  CommitFilter,
+  ScanOptions,
+  CollectProgress,
+  CollectResult,
+  DiffSummary,
+  HotspotOptions,
+  Hotspots,
+  MergePolicy,
+  OwnershipOptions,
+  OwnershipSummary,
+  RepoGroup,
+  ScanResult,
+  SessionOptions,
+  WorkEstimate,
+} from '../types';
 
 export const useGitRepo = () => {
   const selectRepository = async (): Promise<string | null> => {
@@ -19,18 +37,99 @@ export const useGitRepo = () => {
     return await invoke<RepoInfo>('open_repository', { path });
   };
 
-  const getCommits = async (path: string, from: number, to: number): Promise<Commit[]> => {
-    return await invoke<Commit[]>('get_commits', { path, from, to });
+  const scanRepositories = async (
+    root: string,
+    from: number,
+    to: number,
+    options?: ScanOptions
+  ): Promise<ScanResult> => {
+    return await invoke<ScanResult>('scan_repositories', { root, from, to, options });
+  };
+
+  const getCommits = async (
+    path: string,
+    from: number,
+    to: number,
+    filter?: CommitFilter
+  ): Promise<Commit[]> => {
+    return await invoke<Commit[]>('get_commits', { path, from, to, filter });
+  };
+
+  // Collects several repositories concurrently; failed repos are reported in `errors`
+  const collectRepositories = async (
+    paths: string[],
+    from: number,
+    to: number,
+    filter?: CommitFilter,
+    onProgress?: (progress: CollectProgress) => void
+  ): Promise<CollectResult> => {
+    const unlisten = onProgress
+      ? await listen<CollectProgress>('repo-collection-progress', (event) =>
+          onProgress(event.payload)
+        )
+      : null;
+
+    try {
+      return await invoke<CollectResult>('collect_repositories', { paths, from, to, filter });
+    } finally {
+      unlisten?.();
+    }
   };
 
   const getCommitDiff = async (path: string, hash: string): Promise<string> => {
     return await invoke<string>('get_commit_diff', { path, hash });
   };
 
+  const getCommitDiffSummary = async (
+    path: string,
+    hash: string,
+    threshold?: number
+  ): Promise<DiffSummary> => {
+    return await invoke<DiffSummary>('get_commit_diff_summary', { path, hash, threshold });
+  };
+
+  const getHotspots = async (
+    path: string,
+    from: number,
+    to: number,
+    filter?: CommitFilter,
+    options?: HotspotOptions
+  ): Promise<Hotspots> => {
+    return await invoke<Hotspots>('get_hotspots', { path, from, to, filter, options });
+  };
+
+  const getOwnership = async (
+    path: string,
+    from: number,
+    to: number,
+    filter?: CommitFilter,
+    options?: OwnershipOptions
+  ): Promise<OwnershipSummary> => {
+    return await invoke<OwnershipSummary>('get_ownership', { path, from, to, filter, options });
+  };
+
+  const estimateWorkHours = async (
+    repoGroups: RepoGroup[],
+    mergePolicy?: MergePolicy,
+    options?: SessionOptions
+  ): Promise<WorkEstimate> => {
+    return await invoke<WorkEstimate>('estimate_work_hours', {
+      repoGroups,
+      mergePolicy,
+      options,
+    });
+  };
+
   return {
     selectRepository,
     openRepository,
+    scanRepositories,
     getCommits,
+    collectRepositories,
     getCommitDiff,
+    getCommitDiffSummary,
+    getHotspots,
+    getOwnership,
+    estimateWorkHours,
   };
 };
@@ -45,7 +45,9 @@ export function Commits() {
     if (searchKeyword) {
       if (searchKeyword.startsWith('EXACT:')) {
         const exactAuthor = searchKeyword.substring(6);
-        if (c.author !== exactAuthor) return false;
+        const isParticipant =
+          c.author === exactAuthor || !!c.coAuthors?.some((co) => co.name === exactAuthor);
+        if (!isParticipant) return false;
       } else {
         const keyword = searchKeyword.toLowerCase();
         const matchesSearch =
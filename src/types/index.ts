@@ -1,5 +1,80 @@
 // Core domain types for Commitly
 
+export type ChangeKind = 'added' | 'modified' | 'deleted' | 'renamed' | 'copied' | 'typechange';
+
+export interface FileChange {
+  path: string;
+  oldPath?: string;
+  kind: ChangeKind;
+  insertions: number;
+  deletions: number;
+  binary: boolean;
+}
+
+export interface HunkSummary {
+  oldStart: number;
+  oldLines: number;
+  newStart: number;
+  newLines: number;
+  context?: string; // Enclosing function from the hunk header
+}
+
+export interface FileDiffSummary extends FileChange {
+  hunks: HunkSummary[];
+  addedSymbols?: string[];
+  removedSymbols?: string[];
+}
+
+// Structured diff summary (matches Rust DiffSummary)
+export interface DiffSummary {
+  files: FileDiffSummary[];
+  insertions: number;
+  deletions: number;
+  patchSize: number;
+  summarized: boolean; // True when `text` is a summary rather than the full patch
+  text: string;
+}
+
+export interface ConventionalCommit {
+  type: string;
+  scope?: string;
+  breaking: boolean;
+  breakingNote?: string;
+  description: string;
+  inferred: boolean;
+}
+
+// Pair-programming partner from a Co-authored-by trailer
+export interface CoAuthor {
+  name: string;
+  email: string;
+}
+
+// Submodule pointer update recorded on a superproject commit
+export interface SubmoduleUpdate {
+  path: string;
+  from?: string;
+  to?: string;
+  commits: string[];
+}
+
+// Tag pointing at a commit inside the report window
+export interface Release {
+  name: string;
+  target: string;
+  annotated: boolean;
+  tagger?: string;
+  taggerEmail?: string;
+  timestamp: number;
+  message?: string;
+  previousTag?: string;
+}
+
+export interface TicketReference {
+  id: string;
+  kind: string;
+}
+
 export interface Commit {
   hash: string;
   author: string;
@@ -7,13 +82,109 @@ export interface Commit {
   timestamp: number;
   message: string;
   diff?: string;
+  branches?: string[];
+  parents?: string[];
+  mergedCommits?: Commit[];
+  files?: FileChange[];
+  conventional?: ConventionalCommit;
+  references?: TicketReference[];
+  submoduleUpdates?: SubmoduleUpdate[];
+  coAuthors?: CoAuthor[];
 }
 
+// Commit collection filter (matches Rust CommitFilter)
+export type AuthorMatchMode = 'exact' | 'ignore-case' | 'glob';
+
+export type BranchSelection =
+  | { mode: 'head' }
+  | { mode: 'named'; branches: string[] }
+  | { mode: 'all-local' }
+  | { mode: 'glob'; pattern: string };
+
+export type TimeKey = 'committer' | 'author';
+
+export type MergePolicy = 'all' | 'exclude-merges' | 'first-parent' | 'merges-only';
+
+export interface CommitFilter {
+  authors?: string[];
+  authorMatch?: AuthorMatchMode;
+  branches?: BranchSelection;
+  timeKey?: TimeKey;
+  mergePolicy?: MergePolicy;
+  paths?: string[]; // globs; prefix with "!" to exclude
+  includeSubmodules?: boolean;
+}
+
+// Workspace repository scan options (matches Rust ScanOptions)
+export interface ScanOptions {
+  maxDepth?: number;
+  ignore?: string[];
+  branches?: BranchSelection; // Should match the branches used for collection
+  timeKey?: TimeKey; // Should match the time key used for collection
+}
+
+// HEAD state of an opened repository (matches Rust RepoState)
+export type RepoState =
+  | { kind: 'normal' }
+  | { kind: 'empty'; branch: string }
+  | { kind: 'unborn'; branch: string }
+  | { kind: 'detached'; commit: string };
+
 export interface RepoInfo {
   path: string;
   name: string;
   branch: string;
   totalCommits: number;
+  localBranches?: string[];
+  state?: RepoState;
+  shallow?: boolean;
+}
+
+// Per-repo collection warning (matches Rust RepoWarning)
+export type RepoWarning =
+  | { kind: 'empty' }
+  | { kind: 'unborn-branch'; branch: string }
+  | { kind: 'detached-head'; commit: string }
+  | { kind: 'shallow-history'; boundary: number }
+  | { kind: 'unknown-timezone'; name: string }
+  | { kind: 'index-not-saved'; message: string }
+  | { kind: 'failed'; message: string };
+
+// Commits of one repository as sent to report generation
+export interface RepoGroup {
+  repo_id: string;
+  repo_name: string;
+  repo_path: string;
+  commits: Commit[];
+  releases?: Release[];
+  submodules?: RepoGroup[];
+  warnings?: RepoWarning[];
+}
+
+// Emitted as `repo-collection-progress` after each repository finishes
+export interface CollectProgress {
+  completed: number;
+  total: number;
+  repoPath: string;
+  repoName: string;
+  commits: number;
+  error?: string;
+}
+
+export interface RepoError {
+  repoPath: string;
+  message: string;
+}
+
+export interface CollectResult {
+  repoGroups: RepoGroup[];
+  errors: RepoError[];
+}
+
+// Workspace scan result: active repositories and unreadable ones (matches Rust ScanResult)
+export interface ScanResult {
+  repos: RepoInfo[];
+  errors: RepoError[];
 }
 
 export interface Report {
@@ -25,6 +196,8 @@ export interface Report {
   content: string;
   commits: Commit[];
   repoIds: string[]; // References to repositories involved in this report
+  warnings?: string[]; // Per-repo collection warnings ("repo: message")
+  incomplete?: boolean; // True when shallow history or failed repos left gaps
   metadata?: {
     commitRange?: { from: number; to: number };
     generationParams?: Record<string, unknown>;
@@ -81,11 +254,47 @@ export interface ProxyConfig {
   httpsProxy?: string;
 }
 
+// Author alias: merges several names/emails into one canonical identity
+export interface AuthorAlias {
+  name: string;
+  email: string;
+  aliases: string[];
+}
+
+// Ticket reference extraction rule (first capture group is the ticket id)
+export interface TicketPattern {
+  name: string;
+  pattern: string;
+  exclude_prefixes?: string[]; // Ids whose part before the first "-" matches are dropped
+}
+
+// Opt-in diff attachment for report prompts (matches Rust DiffInclusion)
+export interface DiffInclusion {
+  enabled: boolean;
+  maxChars: number; // Character budget shared by all attached diffs
+  summaryThreshold: number; // Bytes above which a commit's diff is summarized
+  excludePaths: string[]; // Globs never attached (lockfiles, generated, vendored)
+}
+
+// Custom language rule, checked before the built-in table (matches Rust LanguageMapping)
+export interface LanguageMapping {
+  pattern: string; // File-name glob, or a repo path glob when it contains '/'
+  language: string; // Used only for source files
+  category?: FileCategory; // Defaults to 'source'
+}
+
 export interface AppConfig {
   llm_provider: LLMProvider;
   exportFormat: 'markdown' | 'html' | 'pdf';
-  timezone: string;
+  timezone: string; // IANA name, 'UTC', 'Local' or a fixed offset such as '+08:00'
   proxy_config?: ProxyConfig;
+  author_aliases?: AuthorAlias[];
+  ticket_patterns?: TicketPattern[];
+  diff_inclusion?: DiffInclusion;
+  hotspots?: HotspotOptions;
+  language_mappings?: LanguageMapping[];
+  sessions?: SessionOptions;
+  ownership?: OwnershipOptions; // Set to include code ownership in reports
 }
 
 export interface RepoStats {
@@ -94,6 +303,116 @@ export interface RepoStats {
   filesChanged: number;
   insertions: number;
   deletions: number;
+  languages: LanguageShare[];
+  activity: ActivityStats;
+}
+
+// Commit time distribution in the configured timezone (matches Rust ActivityStats)
+export interface ActivityStats {
+  timezone: string;
+  byWeekday: number[]; // Monday first
+  byHour: number[]; // 0-23
+  heatmap: number[][]; // 7 rows (Monday first) x 24 hours
+  activeDays: number;
+  spanDays: number; // Days from the first to the last commit day, inclusive
+  longestStreak: number;
+  offHoursCommits: number; // Weekdays before 9:00 or after 19:00
+  weekendCommits: number;
+  days: DayActivity[];
+}
+
+export interface DayActivity {
+  date: string; // YYYY-MM-DD
+  commits: number;
+  firstTime: string; // HH:MM
+  lastTime: string;
+}
+
+export type FileCategory = 'source' | 'test' | 'docs' | 'config' | 'other';
+
+// Share of changed lines per language or file category (matches Rust LanguageShare)
+export interface LanguageShare {
+  name: string; // Language for source files, otherwise Tests / Docs / Config / Other
+  category: FileCategory;
+  files: number;
+  insertions: number;
+  deletions: number;
+  percent: number;
+}
+
+// Work-session estimator options, same algorithm as git-hours (matches Rust SessionOptions)
+export interface SessionOptions {
+  maxGapMinutes: number; // Commits closer than this belong to one session
+  firstCommitMinutes: number; // Time credited before each session's first commit
+}
+
+// Estimated hours from commit timestamps (matches Rust WorkEstimate)
+export interface WorkEstimate {
+  totalHours: number;
+  sessions: number;
+  maxGapMinutes: number;
+  firstCommitMinutes: number;
+  byDay: EstimatedHours[]; // Local dates (YYYY-MM-DD), ascending
+  byRepo: EstimatedHours[];
+  byAuthor: EstimatedHours[];
+}
+
+export interface EstimatedHours {
+  name: string;
+  hours: number;
+  sessions: number;
+  commits: number;
+}
+
+// Churn analysis options (matches Rust HotspotOptions)
+export interface HotspotOptions {
+  depth: number; // Directory levels kept when rolling files up
+  limit: number; // Entries kept per list, 0 for all
+}
+
+// A file or directory ranked by churn within the window (matches Rust Hotspot)
+export interface Hotspot {
+  path: string;
+  commits: number;
+  insertions: number;
+  deletions: number;
+  authors: number; // Distinct authors, co-authors included
+}
+
+export interface Hotspots {
+  files: Hotspot[];
+  directories: Hotspot[];
+}
+
+// Blame analysis options (matches Rust OwnershipOptions)
+export interface OwnershipOptions {
+  paths: string[]; // Files at HEAD to blame; empty for files touched in the window
+  timeBudgetMs: number; // Remaining files are skipped once the budget is spent
+}
+
+// Surviving lines attributed to one author (matches Rust OwnerShare)
+export interface OwnerShare {
+  name: string;
+  email: string;
+  lines: number;
+  percent: number;
+}
+
+// Lines and top owners of a top-level directory (matches Rust DirectoryOwnership)
+export interface DirectoryOwnership {
+  path: string; // '.' for files at the repository root
+  lines: number;
+  owners: OwnerShare[];
+}
+
+// Code ownership at HEAD from blame (matches Rust OwnershipSummary)
+export interface OwnershipSummary {
+  totalLines: number;
+  filesBlamed: number;
+  filesSkipped: number; // Binary, oversized, failed or over budget
+  truncated: boolean; // True when the time budget ran out
+  authors: OwnerShare[];
+  directories: DirectoryOwnership[];
 }
 
 // Repository history item for sidebar display